@@ -2,6 +2,7 @@
 // Renders Mandelbrot, Julia, and Burning Ship fractals
 // Uses fragment shader for WebGL2 compatibility
 
+// Layout of FractalUniform in src/fractal/mod.rs
 struct FractalParams {
     center: vec2<f32>,          // offset 0  (8 bytes)
     zoom: f32,                   // offset 8  (4 bytes)
@@ -11,11 +12,32 @@ struct FractalParams {
     fractal_type: u32,           // offset 24 (4 bytes)
     color_scheme: u32,           // offset 28 (4 bytes)
     julia_c: vec2<f32>,          // offset 32 (8 bytes)
-    flags: u32,                  // offset 40 (4 bytes) - bit 0: smooth, bit 1: invert, bit 2: offset
-    _pad: u32,                   // offset 44 (4 bytes)
+    flags: u32,                  // offset 40 (4 bytes) - bit 0: smooth, bit 1: invert, bit 2: offset, bit 3: basins (bit 4 is app-only)
+    rotation: f32,               // offset 44 (4 bytes) - view rotation in radians
     resolution: vec2<f32>,       // offset 48 (8 bytes) - canvas width, height
     ui_offset: f32,              // offset 56 (4 bytes) - horizontal offset for UI panel
     ui_offset_y: f32,            // offset 60 (4 bytes) - vertical offset for centering
+    newton_roots: array<vec4<f32>, 4>, // offset 64 (64 bytes) - Newton polynomial roots, two per vec4
+    root_count: u32,             // offset 128 (4 bytes) - roots in use; 0 iterates z^n - 1
+    relaxation: f32,             // offset 132 (4 bytes) - Newton step factor a
+    center_lo: vec2<f32>,        // offset 136 (8 bytes) - low words of the center (center + center_lo)
+    root_turns: array<vec4<f32>, 2>, // offset 144 (32 bytes) - value reported for reaching each root, four per vec4
+    unity_root_count: u32,       // offset 176 (4 bytes) - roots of z^n = 1 in newton_roots while root_count is 0
+    _padding0: u32,              // offset 180
+    _padding1: u32,              // offset 184
+    _padding2: u32,              // offset 188
+}
+
+// Perturbation against a CPU reference orbit (PerturbationParams in perturbation.rs)
+struct Perturbation {
+    ref_len: u32,                // offset 0 - points in reference_orbit; 0 turns perturbation off
+    scale_exp: i32,              // offset 4 - pixel deltas start out scaled by 2^scale_exp
+    pixel_scale: f32,            // offset 8 - 2^scale_exp / zoom
+    skip: u32,                   // offset 12 - iterations skipped by the series approximation
+    series_exp: i32,             // offset 16 - the series gives deltas scaled by 2^series_exp
+    _padding0: u32,              // offset 20
+    reference_offset: vec2<f32>, // offset 24 - scaled delta of the view center from the reference
+    series: array<vec4<f32>, 4>, // offset 32 - coefficients of w0, w0^2, ..., two per vec4
 }
 
 struct VertexOutput {
@@ -24,6 +46,10 @@ struct VertexOutput {
 }
 
 @group(0) @binding(0) var<uniform> params: FractalParams;
+@group(0) @binding(1) var<uniform> perturbation: Perturbation;
+// Reference orbit Z_n at texel (n % REFERENCE_WIDTH, n / REFERENCE_WIDTH); a texture because
+// WebGL2 has no storage buffers in fragment shaders
+@group(0) @binding(2) var reference_orbit: texture_2d<f32>;
 
 const PI: f32 = 3.14159265359;
 
@@ -44,6 +70,21 @@ const FRACTAL_PHOENIX: u32 = 8u;
 const FLAG_SMOOTH: u32 = 1u;
 const FLAG_INVERT: u32 = 2u;
 const FLAG_OFFSET: u32 = 4u;
+const FLAG_BASINS: u32 = 8u;
+
+// Pixels smaller than this switch to double-single iteration (HIGH_PRECISION_PIXEL_SIZE in constants.rs)
+const HIGH_PRECISION_PIXEL_SIZE: f32 = 1e-6;
+
+// Reference orbit texture width (REFERENCE_WIDTH in perturbation.rs)
+const REFERENCE_WIDTH: u32 = 2048u;
+// Scaled perturbation deltas larger than this are shifted down so w^2 stays finite
+const RESCALE_LIMIT: f32 = 4294967296.0;
+// Series approximation terms (SERIES_TERMS in perturbation.rs)
+const SERIES_TERMS: i32 = 8;
+
+// Root slots in FractalParams.newton_roots (limits::MAX_NEWTON_ROOTS in constants.rs); the roots
+// of z^n = 1 fill them too (MAX_UNITY_ROOTS in cpu/iterate.rs)
+const MAX_NEWTON_ROOTS: u32 = 8u;
 
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
@@ -84,6 +125,230 @@ fn cpow(z: vec2<f32>, n: f32) -> vec2<f32> {
     );
 }
 
+// Double-single arithmetic: a value is the unevaluated sum hi + lo (x + y) of two f32,
+// giving about 48 bits of mantissa. These are the classic error-free transformations;
+// they need every operation rounded on its own, so they must not be algebraically simplified
+// s + e = a + b exactly
+fn ds_two_sum(a: f32, b: f32) -> vec2<f32> {
+    let s = a + b;
+    let v = s - a;
+    let e = (a - (s - v)) + (b - v);
+    return vec2<f32>(s, e);
+}
+
+// s + e = a + b exactly, given |a| >= |b|
+fn ds_quick_two_sum(a: f32, b: f32) -> vec2<f32> {
+    let s = a + b;
+    let e = b - (s - a);
+    return vec2<f32>(s, e);
+}
+
+// Split a into two halves of 12 significant bits each
+fn ds_split(a: f32) -> vec2<f32> {
+    let t = 4097.0 * a;
+    let hi = t - (t - a);
+    return vec2<f32>(hi, a - hi);
+}
+
+// p + e = a * b exactly
+fn ds_two_prod(a: f32, b: f32) -> vec2<f32> {
+    let p = a * b;
+    let sa = ds_split(a);
+    let sb = ds_split(b);
+    let e = ((sa.x * sb.x - p) + sa.x * sb.y + sa.y * sb.x) + sa.y * sb.y;
+    return vec2<f32>(p, e);
+}
+
+fn ds_add(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    let s = ds_two_sum(a.x, b.x);
+    let t = ds_two_sum(a.y, b.y);
+    let v = ds_quick_two_sum(s.x, s.y + t.x);
+    return ds_quick_two_sum(v.x, v.y + t.y);
+}
+
+fn ds_sub(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return ds_add(a, -b);
+}
+
+fn ds_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    let p = ds_two_prod(a.x, b.x);
+    return ds_quick_two_sum(p.x, p.y + (a.x * b.y + a.y * b.x));
+}
+
+fn ds_abs(a: vec2<f32>) -> vec2<f32> {
+    if (a.x < 0.0) {
+        return -a;
+    }
+    return a;
+}
+
+// Escape-time iteration in double-single precision for the power-2 formulas
+// Complex values are vec4(re.hi, re.lo, im.hi, im.lo). Mandelbrot-style types start at z = 0
+// and add the pixel; Julia-style types start at the pixel and add julia_c
+fn iterate_ds(pixel: vec4<f32>, fractal_type: u32, max_iter: u32, escape_radius: f32) -> vec2<f32> {
+    let julia_c = vec4<f32>(params.julia_c.x, 0.0, params.julia_c.y, 0.0);
+    var z = vec4<f32>(0.0);
+    var c = pixel;
+    if (fractal_type == FRACTAL_JULIA || fractal_type == FRACTAL_BUFFALO_JULIA || fractal_type == FRACTAL_CELTIC_JULIA) {
+        z = pixel;
+        c = julia_c;
+    }
+
+    var i: u32 = 0u;
+    let escape2 = escape_radius * escape_radius;
+
+    // The escape test only needs the high words
+    while (i < max_iter && z.x * z.x + z.z * z.z < escape2) {
+        var zr = z.xy;
+        var zi = z.zw;
+        if (fractal_type == FRACTAL_BURNING_SHIP || fractal_type == FRACTAL_BUFFALO_JULIA) {
+            zr = ds_abs(zr);
+            zi = ds_abs(zi);
+        } else if (fractal_type == FRACTAL_TRICORN) {
+            zi = -zi;
+        }
+
+        let zri = ds_mul(zr, zi);
+        var re = ds_sub(ds_mul(zr, zr), ds_mul(zi, zi));
+        var im = ds_add(zri, zri);
+        if (fractal_type == FRACTAL_CELTIC || fractal_type == FRACTAL_CELTIC_JULIA) {
+            re = ds_abs(re);
+        } else if (fractal_type == FRACTAL_BUFFALO_JULIA) {
+            re = ds_sub(re, z.xy);
+            im = ds_sub(im, z.zw);
+        }
+
+        z = vec4<f32>(ds_add(re, c.xy), ds_add(im, c.zw));
+        i = i + 1u;
+    }
+
+    return vec2<f32>(f32(i), z.x * z.x + z.z * z.z);
+}
+
+// 2^e for e in -126..=127, straight from the exponent bits
+fn pow2(e: i32) -> f32 {
+    return bitcast<f32>(u32(e + 127) << 23u);
+}
+
+// v * 2^e, in steps so the scale factor itself never overflows (ldexp is missing from GLSL ES 3.0)
+fn mul_pow2(v: vec2<f32>, e: i32) -> vec2<f32> {
+    var r = v;
+    var k = e;
+    while (k > 126) {
+        r = r * pow2(126);
+        k = k - 126;
+    }
+    while (k < -126) {
+        r = r * pow2(-126);
+        k = k + 126;
+    }
+    return r * pow2(k);
+}
+
+fn reference_point(n: u32) -> vec2<f32> {
+    return textureLoad(reference_orbit, vec2<i32>(i32(n % REFERENCE_WIDTH), i32(n / REFERENCE_WIDTH)), 0).xy;
+}
+
+// |c + d| - |c| in scaled units, with cs = c * 2^k and d a scaled delta
+fn diffabs(c: f32, cs: f32, d: f32) -> f32 {
+    if (c >= 0.0) {
+        if (cs + d >= 0.0) {
+            return d;
+        }
+        return -(2.0 * cs + d);
+    }
+    if (cs + d > 0.0) {
+        return 2.0 * cs + d;
+    }
+    return -d;
+}
+
+// f(Z + d) - f(Z) in scaled units, for d = w * 2^-k and zs = Z * 2^k
+fn perturb_step(fractal_type: u32, z: vec2<f32>, zs: vec2<f32>, w: vec2<f32>, k: i32) -> vec2<f32> {
+    if (fractal_type == FRACTAL_BURNING_SHIP) {
+        let a = diffabs(z.x, zs.x, w.x);
+        let b = diffabs(z.y, zs.y, w.y);
+        let az = abs(z);
+        return vec2<f32>(2.0 * (az.x * a - az.y * b), 2.0 * (az.x * b + a * az.y))
+            + mul_pow2(vec2<f32>(a * a - b * b, 2.0 * a * b), -k);
+    }
+    let d = cmul(z, w) * 2.0 + mul_pow2(cmul(w, w), -k);
+    if (fractal_type == FRACTAL_TRICORN) {
+        return vec2<f32>(d.x, -d.y);
+    }
+    return d;
+}
+
+// Scaled delta after perturbation.skip iterations from the series approximation (Horner's rule)
+fn eval_series(w0: vec2<f32>) -> vec2<f32> {
+    var w = vec2<f32>(0.0);
+    for (var t = SERIES_TERMS - 1; t >= 0; t = t - 1) {
+        let row = perturbation.series[t / 2];
+        let c = select(row.xy, row.zw, t % 2 == 1);
+        w = cmul(w + c, w0);
+    }
+    return w;
+}
+
+// Escape-time iteration by perturbation around the reference orbit, computed at or near the view center
+// offset is the pixel's rotated NDC offset (delta offset / zoom from the view center, shifted
+// by reference_offset to a delta from the reference). The delta is kept as
+// w * 2^-k, with k starting at scale_exp and only shrinking, so it never underflows.
+// A pixel is rebased onto Z_0 = 0 when the reference runs out, or when it comes closer to 0
+// than to the reference, where the delta would lose its precision (a glitch)
+fn iterate_perturbed(offset: vec2<f32>, fractal_type: u32, max_iter: u32, escape_radius: f32) -> vec2<f32> {
+    let escape2 = escape_radius * escape_radius;
+    let k0 = perturbation.scale_exp;
+    let w0 = offset * perturbation.pixel_scale + perturbation.reference_offset;
+
+    var w = vec2<f32>(0.0);
+    var k = k0;
+    // Position in the reference orbit; runs behind i once the pixel has been rebased
+    var n: u32 = 0u;
+    var z = vec2<f32>(0.0);
+    var i: u32 = 0u;
+
+    // Start past the iterations the series approximation covers
+    if (perturbation.skip > 0u) {
+        w = eval_series(w0);
+        k = perturbation.series_exp;
+        n = perturbation.skip;
+        i = min(n, max_iter);
+        z = reference_point(n) + mul_pow2(w, -k);
+    }
+
+    while (i < max_iter) {
+        let zn = reference_point(n);
+        w = perturb_step(fractal_type, zn, mul_pow2(zn, k), w, k) + mul_pow2(w0, k - k0);
+        n = n + 1u;
+        i = i + 1u;
+
+        if (max(abs(w.x), abs(w.y)) > RESCALE_LIMIT && k > 0) {
+            let shift = min(k, 32);
+            w = mul_pow2(w, -shift);
+            k = k - shift;
+        }
+
+        let zr = reference_point(n);
+        z = zr + mul_pow2(w, -k);
+        if (dot(z, z) >= escape2) {
+            break;
+        }
+
+        let zs = mul_pow2(zr, k) + w;
+        if (n + 1u >= perturbation.ref_len) {
+            w = z;
+            k = 0;
+            n = 0u;
+        } else if (dot(zs, zs) < dot(w, w)) {
+            w = zs;
+            n = 0u;
+        }
+    }
+
+    return vec2<f32>(f32(i), dot(z, z));
+}
+
 // Fractal iteration functions
 fn iterate_mandelbrot(c: vec2<f32>, power: f32, max_iter: u32, escape_radius: f32) -> vec2<f32> {
     var z = vec2<f32>(0.0, 0.0);
@@ -215,26 +480,35 @@ fn iterate_celtic_julia(z_init: vec2<f32>, c: vec2<f32>, power: f32, max_iter: u
     return vec2<f32>(f32(i), dot(z, z));
 }
 
-// Newton fractal: z = z - (z^n - 1) / (n * z^(n-1))
+// Newton fractal: z = z - a * (z^n - 1) / (n * z^(n-1)), with relaxation factor a
 // Finds roots of z^n = 1, colors by convergence rate
-fn iterate_newton(c: vec2<f32>, power: f32, max_iter: u32, escape_radius: f32) -> vec2<f32> {
+// Returns iteration count, a distance-to-root term for smooth coloring, and the root reached
+// as a fraction of a full turn (-1 if the iteration did not converge)
+fn iterate_newton(c: vec2<f32>, power: f32, relaxation: f32, max_iter: u32, escape_radius: f32) -> vec3<f32> {
     var z = c;
     var i: u32 = 0u;
     let tolerance = 0.000001;
 
-    // Pre-compute the roots of unity for coloring
-    // For z^3 = 1, roots are at angles 0, 2pi/3, 4pi/3
-    let root1 = vec2<f32>(1.0, 0.0);
-    let root2 = vec2<f32>(cos(2.0 * PI / power), sin(2.0 * PI / power));
-    let root3 = vec2<f32>(cos(4.0 * PI / power), sin(4.0 * PI / power));
-
-    while (i < max_iter) {
-        // Check if we've converged to a root
-        let d1 = length(z - root1);
-        let d2 = length(z - root2);
-        let d3 = length(z - root3);
+    // Roots of z^n = 1 as cpow sees them (principal branch), worked out once per frame on the CPU
+    // (newton_roots in cpu/iterate.rs). For whole n these are exactly the n roots of unity
+    let root_count = min(params.unity_root_count, MAX_NEWTON_ROOTS);
+
+    var nearest: u32 = 0u;
+    var nearest_dist: f32 = 0.0;
+
+    loop {
+        // Find the closest root; stop once it is reached
+        nearest = 0u;
+        nearest_dist = length(z - newton_root(0u));
+        for (var r: u32 = 1u; r < root_count; r = r + 1u) {
+            let d = length(z - newton_root(r));
+            if (d < nearest_dist) {
+                nearest = r;
+                nearest_dist = d;
+            }
+        }
 
-        if (d1 < tolerance || d2 < tolerance || d3 < tolerance) {
+        if (nearest_dist < tolerance || i >= max_iter) {
             break;
         }
 
@@ -267,20 +541,87 @@ fn iterate_newton(c: vec2<f32>, power: f32, max_iter: u32, escape_radius: f32) -
         // z^n - 1
         let f_z = zn - vec2<f32>(1.0, 0.0);
 
-        // f(z) / f'(z)
-        let correction = cmul(f_z, inv_nz);
+        // a * f(z) / f'(z)
+        let correction = cmul(f_z, inv_nz) * relaxation;
 
         z = z - correction;
         i = i + 1u;
     }
 
-    // Return iteration count and distance to nearest root for smooth coloring
-    let d1 = length(z - root1);
-    let d2 = length(z - root2);
-    let d3 = length(z - root3);
-    let min_dist = min(d1, min(d2, d3));
+    // Iteration count and distance to nearest root for smooth coloring, plus the basin
+    var root = -1.0;
+    if (nearest_dist < tolerance) {
+        root = root_turn(nearest);
+    }
+    return vec3<f32>(f32(i), nearest_dist * 1000.0 + 1.0, root);
+}
+
+// Newton root r, custom or of z^n = 1; roots are packed two per vec4 since uniform arrays need a 16-byte stride
+fn newton_root(r: u32) -> vec2<f32> {
+    let pair = params.newton_roots[r / 2u];
+    if (r % 2u == 0u) {
+        return pair.xy;
+    }
+    return pair.zw;
+}
+
+// Value reported for reaching root r; packed four per vec4 like newton_roots
+fn root_turn(r: u32) -> f32 {
+    return params.root_turns[r / 4u][r % 4u];
+}
+
+// Newton fractal for the polynomial with the roots in params: z = z - a * p(z) / p'(z)
+// p'(z) / p(z) is the sum of 1 / (z - r) over the roots, so the polynomial is never expanded
+// Returns the same values as iterate_newton, with the root reached given as its index
+// divided by the root count
+fn iterate_newton_roots(c: vec2<f32>, relaxation: f32, max_iter: u32) -> vec3<f32> {
+    var z = c;
+    var i: u32 = 0u;
+    let tolerance = 0.000001;
+    let root_count = min(params.root_count, MAX_NEWTON_ROOTS);
+
+    var nearest: u32 = 0u;
+    var nearest_dist: f32 = 0.0;
+
+    loop {
+        // Find the closest root; stop once it is reached
+        nearest = 0u;
+        nearest_dist = length(z - newton_root(0u));
+        for (var r: u32 = 1u; r < root_count; r = r + 1u) {
+            let d = length(z - newton_root(r));
+            if (d < nearest_dist) {
+                nearest = r;
+                nearest_dist = d;
+            }
+        }
+
+        if (nearest_dist < tolerance || i >= max_iter) {
+            break;
+        }
+
+        // sum of 1/(z - r) = conj(z - r) / |z - r|^2
+        var sum = vec2<f32>(0.0, 0.0);
+        for (var r: u32 = 0u; r < root_count; r = r + 1u) {
+            let d = z - newton_root(r);
+            sum = sum + vec2<f32>(d.x, -d.y) / dot(d, d);
+        }
+
+        // Critical point of p, or so far out that the step would blow up
+        let sum_mag2 = dot(sum, sum);
+        if (sum_mag2 < 0.0000001) {
+            break;
+        }
 
-    return vec2<f32>(f32(i), min_dist * 1000.0 + 1.0);
+        // a / sum = a * conj(sum) / |sum|^2
+        z = z - vec2<f32>(sum.x, -sum.y) * (relaxation / sum_mag2);
+        i = i + 1u;
+    }
+
+    var root = -1.0;
+    if (nearest_dist < tolerance) {
+        root = f32(nearest) / f32(root_count);
+    }
+    return vec3<f32>(f32(i), nearest_dist * 1000.0 + 1.0, root);
 }
 
 // Phoenix fractal: z_new = z^2 + c + p * z_prev
@@ -545,6 +886,16 @@ fn palette_thermal(t: f32) -> vec3<f32> {
     }
 }
 
+// Newton basin coloring: each root gets its own hue (by its angle), darkening with t
+// Points that did not reach a root (root < 0) are black
+fn basin_color(root: f32, t: f32) -> vec3<f32> {
+    if (root < 0.0) {
+        return vec3<f32>(0.0, 0.0, 0.0);
+    }
+    let hue = clamp(abs(fract(root + vec3<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0, vec3<f32>(0.0), vec3<f32>(1.0));
+    return hue * exp(-3.0 * t);
+}
+
 fn get_color(t: f32, scheme: u32) -> vec3<f32> {
     switch(scheme) {
         case 0u: { return palette_classic(t); }
@@ -594,41 +945,74 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         (uv.y - 0.5) * 2.0 + params.ui_offset_y
     );
 
-    let c = params.center + ndc / params.zoom;
+    // Rotate about the visible center (ndc origin)
+    let rot_cos = cos(params.rotation);
+    let rot_sin = sin(params.rotation);
+    let rotated = vec2<f32>(
+        ndc.x * rot_cos - ndc.y * rot_sin,
+        ndc.x * rot_sin + ndc.y * rot_cos
+    );
+
+    let offset = rotated / params.zoom;
+    let c = params.center + offset;
 
     // Iterate based on fractal type
     var result: vec2<f32>;
-
-    switch(params.fractal_type) {
-        case FRACTAL_MANDELBROT: {
-            result = iterate_mandelbrot(c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_JULIA: {
-            result = iterate_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_BURNING_SHIP: {
-            result = iterate_burning_ship(c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_TRICORN: {
-            result = iterate_tricorn(c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_CELTIC: {
-            result = iterate_celtic(c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_BUFFALO_JULIA: {
-            result = iterate_buffalo_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_CELTIC_JULIA: {
-            result = iterate_celtic_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_NEWTON: {
-            result = iterate_newton(c, params.power, params.max_iter, params.escape_radius);
-        }
-        case FRACTAL_PHOENIX: {
-            result = iterate_phoenix(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
-        }
-        default: {
-            result = iterate_mandelbrot(c, params.power, params.max_iter, params.escape_radius);
+    // Root reached by Newton as a fraction of a turn; -1 for everything else
+    var root = -1.0;
+
+    // Once f32 cannot tell neighbouring pixels apart, iterate the power-2 escape-time formulas
+    // in double-single, with the full-precision center (see FractalParams::high_precision).
+    // Deeper still, the CPU uploads a reference orbit and pixels iterate by perturbation
+    let pixel_size = 2.0 / (params.resolution.y * params.zoom);
+    let ds_supported = params.fractal_type != FRACTAL_NEWTON && params.fractal_type != FRACTAL_PHOENIX && params.power == 2.0;
+    if (perturbation.ref_len > 0u) {
+        result = iterate_perturbed(rotated, params.fractal_type, params.max_iter, params.escape_radius);
+    } else if (ds_supported && pixel_size < HIGH_PRECISION_PIXEL_SIZE) {
+        let pixel = vec4<f32>(
+            ds_add(vec2<f32>(params.center.x, params.center_lo.x), vec2<f32>(offset.x, 0.0)),
+            ds_add(vec2<f32>(params.center.y, params.center_lo.y), vec2<f32>(offset.y, 0.0))
+        );
+        result = iterate_ds(pixel, params.fractal_type, params.max_iter, params.escape_radius);
+    } else {
+        switch(params.fractal_type) {
+            case FRACTAL_MANDELBROT: {
+                result = iterate_mandelbrot(c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_JULIA: {
+                result = iterate_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_BURNING_SHIP: {
+                result = iterate_burning_ship(c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_TRICORN: {
+                result = iterate_tricorn(c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_CELTIC: {
+                result = iterate_celtic(c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_BUFFALO_JULIA: {
+                result = iterate_buffalo_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_CELTIC_JULIA: {
+                result = iterate_celtic_julia(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
+            }
+            case FRACTAL_NEWTON: {
+                var newton: vec3<f32>;
+                if (params.root_count > 0u) {
+                    newton = iterate_newton_roots(c, params.relaxation, params.max_iter);
+                } else {
+                    newton = iterate_newton(c, params.power, params.relaxation, params.max_iter, params.escape_radius);
+                }
+                result = newton.xy;
+                root = newton.z;
+            }
+            case FRACTAL_PHOENIX: {
+                result = iterate_phoenix(c, params.julia_c, params.power, params.max_iter, params.escape_radius);
+            }
+            default: {
+                result = iterate_mandelbrot(c, params.power, params.max_iter, params.escape_radius);
+            }
         }
     }
 
@@ -663,7 +1047,11 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
             t = 1.0 - t;
         }
 
-        color = get_color(t, params.color_scheme);
+        if ((params.flags & FLAG_BASINS) != 0u && params.fractal_type == FRACTAL_NEWTON) {
+            color = basin_color(root, t);
+        } else {
+            color = get_color(t, params.color_scheme);
+        }
     }
 
     return vec4<f32>(color, 1.0);
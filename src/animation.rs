@@ -0,0 +1,352 @@
+//! Time-based navigation animation: drag momentum, eased wheel zoom and fly-to transitions
+//! Every step takes an explicit time in seconds, so results only depend on the
+//! inputs and not on the frame rate
+
+use std::collections::VecDeque;
+
+use crate::constants::{animation, limits};
+use crate::fractal::{Coordinate, FractalParams};
+use crate::viewport::Viewport;
+
+/// Cubic ease-out: fast start, gentle stop; maps 0..1 onto 0..1
+pub fn ease_out_cubic(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Cubic ease-in-out: gentle start and stop; maps 0..1 onto 0..1
+pub fn ease_in_out_cubic(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// Zoom easing from `start_log_zoom` to `target_log_zoom` about a fixed screen point
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ZoomAnimation {
+    start_time: f64,
+    start_log_zoom: f64,
+    target_log_zoom: f64,
+    anchor: [f64; 2],
+}
+
+/// Smooth and efficient zoom-and-pan path between two views (van Wijk & Nuij, 2003)
+/// The view zooms out while it travels, so the destination comes into sight early
+/// instead of the view racing across the plane at full zoom
+/// The path only depends on where the ends are relative to each other, so the animator passes
+/// offsets from the exact start center, which f64 resolves at any zoom
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlightPath {
+    from: [f64; 2],
+    to: [f64; 2],
+    /// Distance between the two centers
+    distance: f64,
+    zooms: [f64; 2],
+    /// Visible height in the complex plane at each end
+    widths: [f64; 2],
+    /// `r0` of the paper; unused for a pure zoom
+    r0: f64,
+    /// Path length `S` in the paper's units
+    length: f64,
+}
+
+impl FlightPath {
+    pub fn new(from: [f64; 2], from_zoom: f64, to: [f64; 2], to_zoom: f64) -> Self {
+        let rho = animation::FLY_RHO;
+        let (w0, w1) = (2.0 / from_zoom, 2.0 / to_zoom);
+        let distance = (to[0] - from[0]).hypot(to[1] - from[1]);
+        let mut path = Self {
+            from,
+            to,
+            distance,
+            zooms: [from_zoom, to_zoom],
+            widths: [w0, w1],
+            r0: 0.0,
+            length: 0.0,
+        };
+
+        if path.is_zoom_only() {
+            // No real travel: zoom at a constant rate
+            path.length = (w1 / w0).ln().abs() / rho;
+            return path;
+        }
+
+        let (rho2, u2) = (rho * rho, distance * distance);
+        let b0 = (w1 * w1 - w0 * w0 + rho2 * rho2 * u2) / (2.0 * w0 * rho2 * distance);
+        let b1 = (w1 * w1 - w0 * w0 - rho2 * rho2 * u2) / (2.0 * w1 * rho2 * distance);
+        // r = ln(-b + sqrt(b^2 + 1)), written without the cancellation for large b
+        path.r0 = -b0.asinh();
+        path.length = (-b1.asinh() - path.r0) / rho;
+        path
+    }
+
+    fn is_zoom_only(&self) -> bool {
+        self.distance <= 1e-9 * self.widths[0].min(self.widths[1])
+    }
+
+    /// Length of the path; proportional to how long the trip feels at constant speed
+    pub fn length(&self) -> f64 {
+        self.length
+    }
+
+    /// Center and zoom a fraction `t` of the way along the path
+    pub fn at(&self, t: f64) -> ([f64; 2], f64) {
+        let t = t.clamp(0.0, 1.0);
+        if t == 0.0 {
+            return (self.from, self.zooms[0]);
+        }
+        if t == 1.0 {
+            return (self.to, self.zooms[1]);
+        }
+
+        let rho = animation::FLY_RHO;
+        let s = self.length * t;
+        let [w0, w1] = self.widths;
+        let (traveled, width) = if self.is_zoom_only() {
+            (t * self.distance, w0 * (w1 / w0).powf(t))
+        } else {
+            let r = rho * s + self.r0;
+            let cosh_r0 = self.r0.cosh();
+            (
+                w0 / (rho * rho) * (cosh_r0 * r.tanh() - self.r0.sinh()),
+                w0 * cosh_r0 / r.cosh(),
+            )
+        };
+
+        let k = if self.distance > 0.0 { traveled / self.distance } else { 0.0 };
+        let center = [
+            self.from[0] + (self.to[0] - self.from[0]) * k,
+            self.from[1] + (self.to[1] - self.from[1]) * k,
+        ];
+        (center, 2.0 / width)
+    }
+}
+
+/// Transition to `target`, with `julia_c` and `power` blended along the way
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Flight {
+    start_time: f64,
+    duration: f64,
+    path: FlightPath,
+    start: FractalParams,
+    target: FractalParams,
+}
+
+/// The animated part of the parameters, as last written by a tick
+type ViewState = ([Coordinate; 2], f64, [f32; 2], f32);
+
+fn view_state(params: &FractalParams) -> ViewState {
+    (params.center, params.zoom, params.julia_c, params.power)
+}
+
+/// Drives momentum after drag releases, eased zoom steps and fly-to transitions
+pub struct Animator {
+    epoch: web_time::Instant,
+    /// Momentum in pixels per second
+    velocity: [f64; 2],
+    zoom: Option<ZoomAnimation>,
+    flight: Option<Flight>,
+    /// Recent drag moves as (time, delta), for estimating the release velocity
+    drag_samples: VecDeque<(f64, [f64; 2])>,
+    last_tick: Option<f64>,
+    /// State written by the last tick; anything else means the view was changed elsewhere
+    expected: Option<ViewState>,
+}
+
+impl Animator {
+    pub fn new() -> Self {
+        Self {
+            epoch: web_time::Instant::now(),
+            velocity: [0.0, 0.0],
+            zoom: None,
+            flight: None,
+            drag_samples: VecDeque::new(),
+            last_tick: None,
+            expected: None,
+        }
+    }
+
+    /// Seconds since the animator was created, for callers driven by the wall clock
+    pub fn now(&self) -> f64 {
+        self.epoch.elapsed().as_secs_f64()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.velocity != [0.0, 0.0] || self.zoom.is_some() || self.flight.is_some()
+    }
+
+    /// Cancel momentum, any running zoom and any flight
+    pub fn stop(&mut self) {
+        self.velocity = [0.0, 0.0];
+        self.zoom = None;
+        self.flight = None;
+        self.last_tick = None;
+        self.expected = None;
+    }
+
+    /// A press grabs the view: momentum and flights stop and a new drag starts
+    pub fn grab(&mut self) {
+        self.velocity = [0.0, 0.0];
+        self.flight = None;
+        self.drag_samples.clear();
+    }
+
+    /// Record a drag move of `delta` pixels at time `now`
+    pub fn track_drag(&mut self, delta: [f64; 2], now: f64) {
+        self.drag_samples.push_back((now, delta));
+        while let Some(&(time, _)) = self.drag_samples.front() {
+            if now - time <= animation::VELOCITY_WINDOW {
+                break;
+            }
+            self.drag_samples.pop_front();
+        }
+    }
+
+    /// End a drag at time `now`, carrying on with the recent drag velocity
+    /// Nothing happens if the pointer was held still before release
+    pub fn release(&mut self, now: f64) {
+        let samples = std::mem::take(&mut self.drag_samples);
+        let (Some(&(first, _)), Some(&(last, _))) = (samples.front(), samples.back()) else {
+            return;
+        };
+        if now - last > animation::VELOCITY_WINDOW || last <= first {
+            return;
+        }
+
+        // The first sample's delta happened before the window started
+        let (dx, dy) = samples
+            .iter()
+            .skip(1)
+            .fold((0.0, 0.0), |(x, y), (_, d)| (x + d[0], y + d[1]));
+        let velocity = [dx / (last - first), dy / (last - first)];
+        if velocity[0].hypot(velocity[1]) >= animation::MIN_FLING_SPEED {
+            self.velocity = velocity;
+            self.last_tick = Some(now);
+        }
+    }
+
+    /// Start (or extend) an eased zoom by `factor` about screen point `anchor`
+    /// Steps that arrive while a zoom is running add up toward one target
+    pub fn zoom_at(&mut self, params: &FractalParams, factor: f64, anchor: [f64; 2], now: f64) {
+        self.flight = None;
+        let current = params.zoom.ln();
+        let target = self.zoom.map_or(current, |zoom| zoom.target_log_zoom) + factor.ln();
+        let (min, max) = (limits::MIN_ZOOM.ln(), limits::MAX_ZOOM.ln());
+
+        self.zoom = Some(ZoomAnimation {
+            start_time: now,
+            start_log_zoom: current,
+            target_log_zoom: target.clamp(min, max),
+            anchor,
+        });
+        if self.last_tick.is_none() {
+            self.last_tick = Some(now);
+        }
+    }
+
+    /// Fly from the current view to `target` over `duration` seconds
+    /// Settings that cannot be blended (fractal type, colors, ...) switch right away;
+    /// a zero duration jumps straight to `target`
+    pub fn fly_to(&mut self, params: &mut FractalParams, target: &FractalParams, duration: f64, now: f64) {
+        self.stop();
+        let start = FractalParams {
+            center: params.center,
+            zoom: params.zoom,
+            julia_c: params.julia_c,
+            power: params.power,
+            resolution: params.resolution,
+            ui_offset: params.ui_offset,
+            ui_offset_y: params.ui_offset_y,
+            ..*target
+        };
+        *params = start;
+        if duration <= 0.0 {
+            Self::finish_flight(params, target);
+            return;
+        }
+
+        let offset = [0, 1].map(|i| (target.center[i] - start.center[i]).to_f64());
+        let path = FlightPath::new([0.0, 0.0], start.zoom, offset, target.zoom);
+        self.flight = Some(Flight { start_time: now, duration, path, start, target: *target });
+        self.last_tick = Some(now);
+        self.expected = Some(view_state(params));
+    }
+
+    fn finish_flight(params: &mut FractalParams, target: &FractalParams) {
+        params.center = target.center;
+        params.zoom = target.zoom;
+        params.julia_c = target.julia_c;
+        params.power = target.power;
+    }
+
+    /// Advance to time `now` and write the result into `params`
+    /// `size` is the surface size in physical pixels. Returns true if `params` changed
+    pub fn tick(&mut self, params: &mut FractalParams, size: (u32, u32), now: f64) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+        if self.expected.is_some_and(|expected| expected != view_state(params)) {
+            // Something else moved the view (UI, preset, reset): let it win
+            self.stop();
+            return false;
+        }
+
+        let dt = (now - self.last_tick.unwrap_or(now)).clamp(0.0, animation::MAX_STEP);
+        self.last_tick = Some(now);
+        let mut viewport = Viewport::for_screen(params, size);
+
+        if self.velocity != [0.0, 0.0] {
+            // Exact integral of exponentially decaying velocity, so any frame rate
+            // covers the same distance
+            let decay = (-animation::FRICTION * dt).exp();
+            let travel = (1.0 - decay) / animation::FRICTION;
+            viewport.pan(self.velocity[0] * travel, self.velocity[1] * travel);
+            self.velocity = [self.velocity[0] * decay, self.velocity[1] * decay];
+            if self.velocity[0].hypot(self.velocity[1]) < animation::MIN_SPEED {
+                self.velocity = [0.0, 0.0];
+            }
+        }
+
+        if let Some(zoom) = self.zoom {
+            let t = (now - zoom.start_time) / animation::ZOOM_DURATION;
+            let log_zoom = zoom.start_log_zoom + (zoom.target_log_zoom - zoom.start_log_zoom) * ease_out_cubic(t);
+            viewport.zoom_at(log_zoom.exp() / viewport.zoom, zoom.anchor);
+            if t >= 1.0 {
+                self.zoom = None;
+            }
+        }
+
+        viewport.apply(params);
+
+        if let Some(flight) = self.flight {
+            let t = (now - flight.start_time) / flight.duration;
+            if t >= 1.0 {
+                Self::finish_flight(params, &flight.target);
+                self.flight = None;
+            } else {
+                let eased = ease_in_out_cubic(t);
+                let (offset, zoom) = flight.path.at(eased);
+                let blend = |a: f32, b: f32| a + (b - a) * eased as f32;
+                params.center = [0, 1].map(|i| flight.start.center[i] + Coordinate::from_f64(offset[i]));
+                params.zoom = zoom;
+                params.julia_c = [
+                    blend(flight.start.julia_c[0], flight.target.julia_c[0]),
+                    blend(flight.start.julia_c[1], flight.target.julia_c[1]),
+                ];
+                params.power = blend(flight.start.power, flight.target.power);
+            }
+        }
+
+        self.expected = self.is_active().then(|| view_state(params));
+        true
+    }
+}
+
+impl Default for Animator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,206 @@
+//! User bookmarks: named views kept across sessions
+//! Stored as a versioned JSON document, in `localStorage` on the web and in the config
+//! directory natively. Thumbnails are not stored; they are re-rendered from the parameters
+
+use serde::{Deserialize, Serialize};
+
+use crate::cpu;
+use crate::deeplink;
+use crate::files;
+use crate::fractal::FractalParams;
+use crate::scene::Scene;
+
+/// Current bookmark document version
+pub const BOOKMARKS_VERSION: u32 = 1;
+
+/// Name of the stored bookmark document
+const STORE_NAME: &str = "bookmarks";
+
+/// Thumbnail size in pixels
+pub const THUMBNAIL_SIZE: [u32; 2] = [64, 40];
+
+/// A saved view and the name the user gave it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub params: FractalParams,
+    /// The view's deep link, worked out once when the bookmark is made or loaded
+    #[serde(skip)]
+    key: String,
+}
+
+impl Bookmark {
+    /// Key for caching this view's thumbnail; bookmarks of the same view share it
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// On-disk layout, tagged with the format version it was written with
+#[derive(Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    bookmarks: Vec<Bookmark>,
+}
+
+/// An ordered list of bookmarks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bookmarks {
+    items: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Bookmark> {
+        self.items.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Bookmark> {
+        self.items.iter()
+    }
+
+    /// Append the view in `params`; per-frame layout fields are not saved
+    pub fn add(&mut self, name: &str, params: &FractalParams) {
+        let defaults = FractalParams::default();
+        let params = FractalParams {
+            resolution: defaults.resolution,
+            ui_offset: defaults.ui_offset,
+            ui_offset_y: defaults.ui_offset_y,
+            ..*params
+        };
+        self.items.push(Bookmark {
+            name: name.trim().to_string(),
+            params,
+            key: deeplink::encode(&params),
+        });
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) {
+        if let Some(bookmark) = self.items.get_mut(index) {
+            bookmark.name = name.trim().to_string();
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Bookmark> {
+        (index < self.items.len()).then(|| self.items.remove(index))
+    }
+
+    /// Add every bookmark in `other` after the existing ones
+    pub fn append(&mut self, mut other: Bookmarks) {
+        self.items.append(&mut other.items);
+    }
+
+    /// Move the bookmark at `from` so it ends up at `to`, shifting the ones in between
+    pub fn move_to(&mut self, from: usize, to: usize) {
+        if from < self.items.len() && to < self.items.len() {
+            let bookmark = self.items.remove(from);
+            self.items.insert(to, bookmark);
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let document = Document {
+            version: BOOKMARKS_VERSION,
+            bookmarks: self.items.clone(),
+        };
+        serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to write bookmarks: {e}"))
+    }
+
+    /// Parse a bookmark document
+    /// Each view passes the same validation as a scene file; invalid entries are skipped
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document: Document = serde_json::from_str(text).map_err(|e| format!("Invalid bookmarks: {e}"))?;
+        if document.version == 0 || document.version > BOOKMARKS_VERSION {
+            return Err(format!("Unsupported bookmarks version {}", document.version));
+        }
+
+        let items = document
+            .bookmarks
+            .into_iter()
+            .filter_map(|bookmark| match Scene::new(&bookmark.params).validate() {
+                Ok(params) => Some(Bookmark { params, key: deeplink::encode(&params), ..bookmark }),
+                Err(e) => {
+                    log::warn!("Skipping bookmark {:?}: {}", bookmark.name, e);
+                    None
+                }
+            })
+            .collect();
+        Ok(Self { items })
+    }
+
+    /// Read the user's bookmarks, or an empty list if there are none yet
+    pub fn load() -> Self {
+        let Some(text) = files::read_config(STORE_NAME) else {
+            return Self::new();
+        };
+        Self::from_json(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring stored bookmarks: {}", e);
+            Self::new()
+        })
+    }
+
+    /// Write the bookmarks back to the user's storage
+    pub fn store(&self) -> Result<(), String> {
+        files::write_config(STORE_NAME, &self.to_json()?)
+    }
+}
+
+/// Render a bookmarked view as a small RGBA8 image of [`THUMBNAIL_SIZE`]
+pub fn thumbnail(params: &FractalParams) -> Vec<u8> {
+    let [width, height] = THUMBNAIL_SIZE;
+    let params = FractalParams {
+        resolution: [width as f32, height as f32],
+        ui_offset: 0.0,
+        ui_offset_y: 0.0,
+        ..*params
+    };
+    cpu::render(&params, width, height)
+}
+
+/// A [`thumbnail`] being rendered: on a worker thread natively, on the spot on the web
+pub struct ThumbnailJob {
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: std::sync::mpsc::Receiver<Vec<u8>>,
+    #[cfg(target_arch = "wasm32")]
+    pixels: Option<Vec<u8>>,
+}
+
+impl ThumbnailJob {
+    pub fn start(params: &FractalParams) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let params = *params;
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                // The panel may have been closed meanwhile; nobody is left to tell
+                let _ = sender.send(thumbnail(&params));
+            });
+            Self { receiver }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        Self {
+            pixels: Some(thumbnail(params)),
+        }
+    }
+
+    /// The rendered pixels, once, when the job has finished
+    pub fn poll(&mut self) -> Option<Vec<u8>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.receiver.try_recv().ok();
+
+        #[cfg(target_arch = "wasm32")]
+        self.pixels.take()
+    }
+}
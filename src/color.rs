@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 /// Color scheme enumeration
 /// Must match the WGSL shader switch statement indices exactly
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum ColorScheme {
     Classic = 0,
@@ -94,6 +96,11 @@ impl ColorScheme {
         }
     }
 
+    /// Look up a color scheme by its display name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|cs| cs.name() == name)
+    }
+
     pub fn from_u32(value: u32) -> Self {
         match value {
             0 => ColorScheme::Classic,
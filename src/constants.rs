@@ -6,6 +6,60 @@ pub mod input {
     pub const SCROLL_PIXEL_DIVISOR: f32 = 100.0;
     /// Multiplier for converting scroll value to zoom factor
     pub const ZOOM_SCROLL_MULTIPLIER: f32 = 0.1;
+    /// Smallest box (in physical pixels per side) that triggers a box zoom
+    pub const MIN_BOX_SIZE: f32 = 8.0;
+    /// Fraction of the visible height panned per arrow/WASD key press
+    pub const KEY_PAN_FRACTION: f64 = 0.1;
+    /// Zoom factor per +/- key press
+    pub const KEY_ZOOM_FACTOR: f64 = 1.25;
+    /// Rotation per Q/E key press in radians (5 degrees)
+    pub const KEY_ROTATE_STEP: f64 = std::f64::consts::PI / 36.0;
+    /// Iteration count factor per bracket key press
+    pub const KEY_ITERATION_FACTOR: f32 = 1.25;
+}
+
+/// Navigation animation tuning (times in seconds, speeds in pixels per second)
+pub mod animation {
+    /// Momentum decay rate; velocity falls by a factor of e every 1/FRICTION seconds
+    pub const FRICTION: f64 = 4.0;
+    /// Momentum below this speed stops
+    pub const MIN_SPEED: f64 = 20.0;
+    /// Releases slower than this do not start momentum
+    pub const MIN_FLING_SPEED: f64 = 150.0;
+    /// Drag moves within this window before release set the release velocity
+    pub const VELOCITY_WINDOW: f64 = 0.08;
+    /// Duration of an eased wheel zoom step
+    pub const ZOOM_DURATION: f64 = 0.18;
+    /// Default duration of a fly-to transition between presets
+    pub const FLY_DURATION: f32 = 1.5;
+    /// Longest fly-to duration offered in the UI
+    pub const MAX_FLY_DURATION: f32 = 8.0;
+    /// Fly-to path curvature; larger values zoom out further on long trips
+    pub const FLY_RHO: f64 = std::f64::consts::SQRT_2;
+    /// Longest time step applied at once (e.g. after the tab was in the background)
+    pub const MAX_STEP: f64 = 0.1;
+}
+
+/// Undo/redo history
+pub mod history {
+    /// Oldest entries are dropped beyond this many undo steps
+    pub const MAX_ENTRIES: usize = 100;
+    /// Seconds a change must be still before it becomes its own undo step
+    pub const SETTLE_TIME: f64 = 0.5;
+}
+
+/// Shader precision switching
+pub mod precision {
+    /// Pixels smaller than this (in the complex plane) switch the shader to double-single
+    /// iteration; f32 can no longer place neighbouring pixel centers apart near |c| = 1.
+    /// Must match `HIGH_PRECISION_PIXEL_SIZE` in `fractal.wgsl`
+    pub const HIGH_PRECISION_PIXEL_SIZE: f32 = 1e-6;
+    /// Pixels smaller than this switch from double-single to perturbation against a CPU reference
+    /// orbit; double-single has under 16 bits left per pixel here and perturbation is cheaper
+    pub const PERTURBATION_PIXEL_SIZE: f32 = 1e-10;
+    /// Limbs of an exact view center: 480 fraction bits, down to about 1e-144; pixels at
+    /// [`super::limits::MAX_ZOOM`] are around 1e-103, which leaves room for the reference orbit's guard bits
+    pub const CENTER_LIMBS: usize = 16;
 }
 
 /// Performance warning thresholds per fractal type
@@ -28,13 +82,53 @@ pub mod performance {
 pub mod canvas {
     /// Default canvas width
     pub const DEFAULT_WIDTH: u32 = 1280;
-    /// Default canvas height (used in WASM canvas sizing)
-    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    /// Default canvas height (used in WASM canvas sizing and for scenes, which carry no canvas)
     pub const DEFAULT_HEIGHT: u32 = 800;
     /// Extended height for WASM window
     pub const WASM_WINDOW_HEIGHT: u32 = 1400;
 }
 
+/// Valid ranges for view parameters (sliders, zooming and scene validation)
+pub mod limits {
+    /// Smallest zoom factor
+    pub const MIN_ZOOM: f64 = 1e-10;
+    /// Largest zoom factor; perturbation keeps pixels apart this deep
+    pub const MAX_ZOOM: f64 = 1e100;
+    /// Smallest exponent for Multibrot variants
+    pub const MIN_POWER: f32 = 2.0;
+    /// Largest exponent for Multibrot variants
+    pub const MAX_POWER: f32 = 8.0;
+    /// Smallest escape radius
+    pub const MIN_ESCAPE_RADIUS: f32 = 2.0;
+    /// Largest escape radius
+    pub const MAX_ESCAPE_RADIUS: f32 = 100.0;
+    /// Largest magnitude of either component of the Julia constant
+    pub const MAX_JULIA_C: f32 = 2.0;
+    /// Most roots a custom Newton polynomial can have
+    /// Must match `MAX_NEWTON_ROOTS` in `fractal.wgsl`
+    pub const MAX_NEWTON_ROOTS: usize = 8;
+    /// Largest magnitude of either component of a Newton root
+    pub const MAX_NEWTON_ROOT: f32 = 4.0;
+    /// Smallest Newton relaxation factor
+    pub const MIN_RELAXATION: f32 = 0.1;
+    /// Largest Newton relaxation factor
+    pub const MAX_RELAXATION: f32 = 2.0;
+    /// Smallest factor on the automatic iteration count
+    pub const MIN_ITERATION_BIAS: f32 = 0.25;
+    /// Largest factor on the automatic iteration count
+    pub const MAX_ITERATION_BIAS: f32 = 4.0;
+}
+
+/// Image export limits
+pub mod export {
+    /// Largest tile rendered in one pass (the WebGL2 downlevel texture limit)
+    pub const MAX_TILE_SIZE: u32 = 2048;
+    /// Largest poster edge offered in the UI
+    pub const MAX_POSTER_SIZE: u32 = 32768;
+    /// Default poster edge length
+    pub const DEFAULT_POSTER_SIZE: u32 = 8192;
+}
+
 /// UI panel dimensions
 pub mod ui {
     /// Default side panel width in logical pixels
@@ -43,8 +137,13 @@ pub mod ui {
     pub const MIN_ITERATIONS: f32 = 10.0;
     /// Maximum iteration count for the slider
     pub const MAX_ITERATIONS: f32 = 10000.0;
+    /// Maximum iteration count for views rendered by perturbation, whose deep zooms need far more;
+    /// the reference orbit texture is sized for it
+    pub const MAX_DEEP_ITERATIONS: f32 = 1_000_000.0;
     /// Warning text color (orange) as RGB values
     pub const WARNING_COLOR: (u8, u8, u8) = (255, 180, 0);
+    /// Radius of the draggable Newton root markers in logical pixels
+    pub const ROOT_HANDLE_RADIUS: f32 = 7.0;
 }
 
 /// HTML element IDs for WASM integration
@@ -56,6 +155,13 @@ pub mod html {
     pub const ERROR_BANNER_ID: &str = "error-banner";
 }
 
+/// URL hash deep links
+#[cfg(target_arch = "wasm32")]
+pub mod deeplink {
+    /// Minimum time between history updates while the view is changing
+    pub const HASH_UPDATE_INTERVAL: web_time::Duration = web_time::Duration::from_millis(250);
+}
+
 /// Frame border adjustment (8px each side = 16px total)
 #[cfg(target_arch = "wasm32")]
 pub const FRAME_BORDER_ADJUSTMENT: f64 = 16.0;
@@ -0,0 +1,152 @@
+//! CPU mirror of the double-single (`ds_*`) functions in `shaders/fractal.wgsl`
+//! A value is stored as an unevaluated sum `hi + lo` of two f32, with `|lo|` at most half an
+//! ulp of `hi`, which gives about 48 bits of mantissa using only f32 arithmetic.
+//! The routines are the classic error-free transformations (Dekker, Knuth); they rely on every
+//! operation being rounded on its own, which Rust guarantees for f32
+
+use std::ops::Neg;
+
+use super::iterate::Vec2;
+use crate::fractal::{FractalParams, FractalType};
+
+/// A float-float value `hi + lo`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Ds {
+    pub hi: f32,
+    pub lo: f32,
+}
+
+impl Ds {
+    pub const fn new(hi: f32, lo: f32) -> Self {
+        Self { hi, lo }
+    }
+
+    /// Split an f64 into the nearest float-float pair
+    pub fn from_f64(v: f64) -> Self {
+        let hi = v as f32;
+        Self::new(hi, (v - hi as f64) as f32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.hi as f64 + self.lo as f64
+    }
+
+    pub fn abs(self) -> Self {
+        if self.hi < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl Neg for Ds {
+    type Output = Ds;
+    fn neg(self) -> Ds {
+        Ds::new(-self.hi, -self.lo)
+    }
+}
+
+/// s + e = a + b exactly
+fn two_sum(a: f32, b: f32) -> Ds {
+    let s = a + b;
+    let v = s - a;
+    let e = (a - (s - v)) + (b - v);
+    Ds::new(s, e)
+}
+
+/// s + e = a + b exactly, given |a| >= |b|
+fn quick_two_sum(a: f32, b: f32) -> Ds {
+    let s = a + b;
+    let e = b - (s - a);
+    Ds::new(s, e)
+}
+
+/// Split `a` into two halves of 12 significant bits each
+fn split(a: f32) -> Ds {
+    let t = 4097.0 * a;
+    let hi = t - (t - a);
+    Ds::new(hi, a - hi)
+}
+
+/// p + e = a * b exactly
+fn two_prod(a: f32, b: f32) -> Ds {
+    let p = a * b;
+    let a = split(a);
+    let b = split(b);
+    let e = ((a.hi * b.hi - p) + a.hi * b.lo + a.lo * b.hi) + a.lo * b.lo;
+    Ds::new(p, e)
+}
+
+pub fn ds_add(a: Ds, b: Ds) -> Ds {
+    let s = two_sum(a.hi, b.hi);
+    let t = two_sum(a.lo, b.lo);
+    let v = quick_two_sum(s.hi, s.lo + t.hi);
+    quick_two_sum(v.hi, v.lo + t.lo)
+}
+
+pub fn ds_sub(a: Ds, b: Ds) -> Ds {
+    ds_add(a, -b)
+}
+
+pub fn ds_mul(a: Ds, b: Ds) -> Ds {
+    let p = two_prod(a.hi, b.hi);
+    quick_two_sum(p.hi, p.lo + (a.hi * b.lo + a.lo * b.hi))
+}
+
+/// Map a pixel center to the complex plane in double-single precision, matching `fs_main`
+/// Only the center needs the extra precision; the offset from it is fine in f32
+pub fn pixel_to_complex_ds(params: &FractalParams, x: f32, y: f32, width: u32, height: u32) -> [Ds; 2] {
+    let offset = super::pixel_offset(params, x, y, width, height) / params.shader_zoom();
+    let (hi, lo) = params.center_words();
+    [
+        ds_add(Ds::new(hi[0], lo[0]), Ds::new(offset.x, 0.0)),
+        ds_add(Ds::new(hi[1], lo[1]), Ds::new(offset.y, 0.0)),
+    ]
+}
+
+/// Escape-time iteration in double-single precision for the power-2 formulas, mirroring `iterate_ds`
+/// Mandelbrot-style types start at `z = 0` and add the pixel; Julia-style types start at the
+/// pixel and add `julia_c`. Returns (iteration count, |z|^2) like the f32 routines
+pub fn iterate_ds(params: &FractalParams, pixel: [Ds; 2]) -> Vec2 {
+    let fractal_type = params.get_fractal_type();
+    let julia_c = [Ds::new(params.julia_c[0], 0.0), Ds::new(params.julia_c[1], 0.0)];
+    let (mut z, c) = if fractal_type.needs_julia_c() {
+        (pixel, julia_c)
+    } else {
+        ([Ds::default(); 2], pixel)
+    };
+
+    let escape2 = params.escape_radius * params.escape_radius;
+    let mut i: u32 = 0;
+
+    // The escape test only needs the high words
+    while i < params.max_iter && z[0].hi * z[0].hi + z[1].hi * z[1].hi < escape2 {
+        let [mut zr, mut zi] = z;
+        match fractal_type {
+            FractalType::BurningShip | FractalType::BuffaloJulia => {
+                zr = zr.abs();
+                zi = zi.abs();
+            }
+            FractalType::Tricorn => zi = -zi,
+            _ => {}
+        }
+
+        let zri = ds_mul(zr, zi);
+        let mut re = ds_sub(ds_mul(zr, zr), ds_mul(zi, zi));
+        let mut im = ds_add(zri, zri);
+        match fractal_type {
+            FractalType::Celtic | FractalType::CelticJulia => re = re.abs(),
+            FractalType::BuffaloJulia => {
+                re = ds_sub(re, z[0]);
+                im = ds_sub(im, z[1]);
+            }
+            _ => {}
+        }
+
+        z = [ds_add(re, c[0]), ds_add(im, c[1])];
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z[0].hi * z[0].hi + z[1].hi * z[1].hi)
+}
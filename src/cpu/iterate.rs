@@ -0,0 +1,447 @@
+//! CPU mirror of the `iterate_*` functions in `shaders/fractal.wgsl`
+//! Each routine follows the shader line by line (including f32 precision) so the
+//! results can be used as ground truth for the GPU path
+
+use std::f32::consts::PI;
+use std::ops::{Add, Div, Mul, Sub};
+
+use super::palette::fract;
+use crate::fractal::{FractalParams, FractalType};
+
+/// Minimal stand-in for WGSL `vec2<f32>`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vec2 {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn dot(self, other: Vec2) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn abs(self) -> Vec2 {
+        Vec2::new(self.x.abs(), self.y.abs())
+    }
+}
+
+impl From<[f32; 2]> for Vec2 {
+    fn from(v: [f32; 2]) -> Self {
+        Vec2::new(v[0], v[1])
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+    fn add(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, rhs: f32) -> Vec2 {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<f32> for Vec2 {
+    type Output = Vec2;
+    fn div(self, rhs: f32) -> Vec2 {
+        Vec2::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+// Complex number operations
+pub fn cmul(a: Vec2, b: Vec2) -> Vec2 {
+    Vec2::new(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x)
+}
+
+pub fn cpow(z: Vec2, n: f32) -> Vec2 {
+    let r = z.length();
+    let theta = z.y.atan2(z.x);
+    let rn = r.powf(n);
+    Vec2::new(rn * (n * theta).cos(), rn * (n * theta).sin())
+}
+
+pub fn iterate_mandelbrot(c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = Vec2::default();
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        if power == 2.0 {
+            z = cmul(z, z) + c;
+        } else {
+            z = cpow(z, power) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+pub fn iterate_julia(z_init: Vec2, c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = z_init;
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        if power == 2.0 {
+            z = cmul(z, z) + c;
+        } else {
+            z = cpow(z, power) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+pub fn iterate_burning_ship(c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = Vec2::default();
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        z = z.abs();
+        if power == 2.0 {
+            z = cmul(z, z) + c;
+        } else {
+            z = cpow(z, power) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+/// Tricorn (Mandelbar): z = conj(z)^2 + c
+pub fn iterate_tricorn(c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = Vec2::default();
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        z = Vec2::new(z.x, -z.y); // conjugate
+        if power == 2.0 {
+            z = cmul(z, z) + c;
+        } else {
+            z = cpow(z, power) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+/// Buffalo Julia: z = abs(z)^2 - z + c (starting from z_init)
+pub fn iterate_buffalo_julia(z_init: Vec2, c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = z_init;
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        let az = z.abs();
+        if power == 2.0 {
+            z = cmul(az, az) - z + c;
+        } else {
+            z = cpow(az, power) - z + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+/// Celtic: z = (abs(real(z^2)), imag(z^2)) + c
+pub fn iterate_celtic(c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = Vec2::default();
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        if power == 2.0 {
+            let z2 = cmul(z, z);
+            z = Vec2::new(z2.x.abs(), z2.y) + c;
+        } else {
+            let zp = cpow(z, power);
+            z = Vec2::new(zp.x.abs(), zp.y) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+pub fn iterate_celtic_julia(z_init: Vec2, c: Vec2, power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = z_init;
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        if power == 2.0 {
+            let z2 = cmul(z, z);
+            z = Vec2::new(z2.x.abs(), z2.y) + c;
+        } else {
+            let zp = cpow(z, power);
+            z = Vec2::new(zp.x.abs(), zp.y) + c;
+        }
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+/// Most roots of z^n = 1 on the principal branch: 8 for the largest power, fewer for fractional ones.
+/// The same as the most custom roots, so either set fits the uniform's root slots
+pub const MAX_UNITY_ROOTS: usize = 8;
+
+/// Roots a Newton iteration converges to, each with the value reported when it is reached
+/// Kept in fixed arrays so they can be worked out once per render and shared by every pixel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NewtonRoots {
+    points: [Vec2; MAX_UNITY_ROOTS],
+    turns: [f32; MAX_UNITY_ROOTS],
+    count: usize,
+}
+
+impl NewtonRoots {
+    /// The roots `params` iterates towards: its custom roots if it has any, else [`newton_roots`]
+    pub fn new(params: &FractalParams) -> Self {
+        let custom = params.roots();
+        if custom.is_empty() {
+            return newton_roots(params.power);
+        }
+
+        // Custom roots are reported as their index divided by the root count
+        let mut roots = Self::empty();
+        for (i, root) in custom.iter().enumerate() {
+            roots.push(Vec2::from(*root), i as f32 / custom.len() as f32);
+        }
+        roots
+    }
+
+    fn empty() -> Self {
+        Self {
+            points: [Vec2::default(); MAX_UNITY_ROOTS],
+            turns: [0.0; MAX_UNITY_ROOTS],
+            count: 0,
+        }
+    }
+
+    fn push(&mut self, point: Vec2, turn: f32) {
+        if self.count < MAX_UNITY_ROOTS {
+            self.points[self.count] = point;
+            self.turns[self.count] = turn;
+            self.count += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn points(&self) -> &[Vec2] {
+        &self.points[..self.count]
+    }
+
+    /// Value reported for each root (see [`iterate_newton`])
+    pub fn turns(&self) -> &[f32] {
+        &self.turns[..self.count]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Vec2, &f32)> {
+        self.points().iter().zip(self.turns())
+    }
+}
+
+impl<'a> IntoIterator for &'a NewtonRoots {
+    type Item = (&'a Vec2, &'a f32);
+    type IntoIter = std::iter::Zip<std::slice::Iter<'a, Vec2>, std::slice::Iter<'a, f32>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.points().iter().zip(self.turns())
+    }
+}
+
+/// Roots of z^n = 1 as `cpow` sees them (principal branch), each with its angle as a
+/// fraction of a full turn: e^(2*pi*i*k/n) for -pi < 2*pi*k/n <= pi.
+/// For whole n these are exactly the n roots of unity
+pub fn newton_roots(power: f32) -> NewtonRoots {
+    let half_turns = (power / 2.0).floor();
+    let mut roots = NewtonRoots::empty();
+    let mut k = -half_turns;
+    while k <= half_turns {
+        let angle = 2.0 * PI * k / power;
+        if angle > -PI {
+            roots.push(Vec2::new(angle.cos(), angle.sin()), fract(k / power));
+        }
+        k += 1.0;
+    }
+    roots
+}
+
+/// Newton fractal: z = z - a * (z^n - 1) / (n * z^(n-1)), with relaxation factor a
+/// Returns iteration count and a distance-to-root term for smooth coloring, plus the root
+/// reached as a fraction of a full turn (-1 if the iteration did not converge);
+/// the shader packs the same three values into a `vec3`
+pub fn iterate_newton(c: Vec2, power: f32, relaxation: f32, max_iter: u32, _escape_radius: f32) -> (Vec2, f32) {
+    iterate_newton_unity(c, &newton_roots(power), power, relaxation, max_iter)
+}
+
+/// [`iterate_newton`] with the roots already worked out by [`newton_roots`]
+pub fn iterate_newton_unity(c: Vec2, roots: &NewtonRoots, power: f32, relaxation: f32, max_iter: u32) -> (Vec2, f32) {
+    let mut z = c;
+    let mut i: u32 = 0;
+    let tolerance = 0.000001;
+
+    let mut nearest;
+    let mut nearest_dist;
+
+    loop {
+        // Find the closest root; stop once it is reached
+        (nearest, nearest_dist) = nearest_root(z, roots.points());
+        if nearest_dist < tolerance || i >= max_iter {
+            break;
+        }
+
+        // Avoid division by zero
+        let z_mag2 = z.dot(z);
+        if z_mag2 < 0.0000001 {
+            break;
+        }
+
+        let zn = cpow(z, power);
+        let zn_minus_1 = cpow(z, power - 1.0);
+
+        let denom = Vec2::new(power, 0.0);
+        let nz = cmul(denom, zn_minus_1);
+        let nz_mag2 = nz.dot(nz);
+        if nz_mag2 < 0.0000001 {
+            break;
+        }
+        // 1/nz = conj(nz) / |nz|^2
+        let inv_nz = Vec2::new(nz.x, -nz.y) / nz_mag2;
+
+        let f_z = zn - Vec2::new(1.0, 0.0);
+        let correction = cmul(f_z, inv_nz) * relaxation;
+
+        z = z - correction;
+        i += 1;
+    }
+
+    let root = if nearest_dist < tolerance { roots.turns()[nearest] } else { -1.0 };
+    (Vec2::new(i as f32, nearest_dist * 1000.0 + 1.0), root)
+}
+
+/// Index of the root closest to `z` and its distance
+fn nearest_root(z: Vec2, roots: &[Vec2]) -> (usize, f32) {
+    let mut nearest = 0;
+    let mut nearest_dist = (z - roots[0]).length();
+    for (r, root) in roots.iter().enumerate().skip(1) {
+        let d = (z - *root).length();
+        if d < nearest_dist {
+            nearest = r;
+            nearest_dist = d;
+        }
+    }
+    (nearest, nearest_dist)
+}
+
+/// Newton fractal for the polynomial with the given roots: z = z - a * p(z) / p'(z)
+/// p'(z) / p(z) is the sum of 1 / (z - r) over the roots, so the polynomial never has to be
+/// expanded. Returns the same values as [`iterate_newton`], with the root reached given as
+/// its index divided by the root count
+pub fn iterate_newton_roots(c: Vec2, roots: &[Vec2], relaxation: f32, max_iter: u32) -> (Vec2, f32) {
+    let mut z = c;
+    let mut i: u32 = 0;
+    let tolerance = 0.000001;
+
+    let (mut nearest, mut nearest_dist) = nearest_root(z, roots);
+    while nearest_dist >= tolerance && i < max_iter {
+        // sum of 1/(z - r) = conj(z - r) / |z - r|^2
+        let mut sum = Vec2::new(0.0, 0.0);
+        for root in roots {
+            let d = z - *root;
+            sum = sum + Vec2::new(d.x, -d.y) / d.dot(d);
+        }
+        // Critical point of p, or so far out that the step would blow up
+        let sum_mag2 = sum.dot(sum);
+        if sum_mag2 < 0.0000001 {
+            break;
+        }
+
+        // a / sum = a * conj(sum) / |sum|^2
+        z = z - Vec2::new(sum.x, -sum.y) * (relaxation / sum_mag2);
+        i += 1;
+        (nearest, nearest_dist) = nearest_root(z, roots);
+    }
+
+    let root = if nearest_dist < tolerance {
+        nearest as f32 / roots.len() as f32
+    } else {
+        -1.0
+    };
+    (Vec2::new(i as f32, nearest_dist * 1000.0 + 1.0), root)
+}
+
+/// Phoenix fractal: z_new = z^2 + c + p * z_prev
+pub fn iterate_phoenix(c: Vec2, p: Vec2, _power: f32, max_iter: u32, escape_radius: f32) -> Vec2 {
+    let mut z = Vec2::default();
+    let mut z_prev = Vec2::default();
+    let mut i: u32 = 0;
+    let escape2 = escape_radius * escape_radius;
+
+    while i < max_iter && z.dot(z) < escape2 {
+        let z_new = cmul(z, z) + c + cmul(p, z_prev);
+        z_prev = z;
+        z = z_new;
+        i += 1;
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
+
+/// Iterate the point `c` with the fractal selected in `params`
+/// Mirrors the `switch` in `fs_main`; returns (iteration count, |z|^2) and the Newton root
+/// reached (-1 for other fractals). `roots` is [`NewtonRoots::new`] of `params`
+pub fn iterate(params: &FractalParams, roots: &NewtonRoots, c: Vec2) -> (Vec2, f32) {
+    let julia_c = Vec2::from(params.julia_c);
+    let (power, max_iter, escape_radius) = (params.power, params.max_iter, params.escape_radius);
+
+    let result = match params.get_fractal_type() {
+        FractalType::Mandelbrot => iterate_mandelbrot(c, power, max_iter, escape_radius),
+        FractalType::Julia => iterate_julia(c, julia_c, power, max_iter, escape_radius),
+        FractalType::BurningShip => iterate_burning_ship(c, power, max_iter, escape_radius),
+        FractalType::Tricorn => iterate_tricorn(c, power, max_iter, escape_radius),
+        FractalType::Celtic => iterate_celtic(c, power, max_iter, escape_radius),
+        FractalType::BuffaloJulia => iterate_buffalo_julia(c, julia_c, power, max_iter, escape_radius),
+        FractalType::CelticJulia => iterate_celtic_julia(c, julia_c, power, max_iter, escape_radius),
+        FractalType::Newton if params.root_count > 0 => {
+            return iterate_newton_roots(c, roots.points(), params.relaxation, max_iter);
+        }
+        FractalType::Newton => return iterate_newton_unity(c, roots, power, params.relaxation, max_iter),
+        FractalType::Phoenix => iterate_phoenix(c, julia_c, power, max_iter, escape_radius),
+    };
+    (result, -1.0)
+}
@@ -0,0 +1,190 @@
+//! Pure-Rust reference renderer
+//! Mirrors `shaders/fractal.wgsl` so images can be produced without a GPU
+//! and the shader math can be checked against a known-good implementation
+
+pub mod double_single;
+pub mod iterate;
+pub mod palette;
+pub mod perturbation;
+
+use crate::color::ColorScheme;
+use crate::fractal::{FractalParams, FractalType};
+use crate::perturbation::{PerturbationParams, ReferenceOrbit};
+
+pub use iterate::{NewtonRoots, Vec2};
+
+/// Offset of a pixel center from the view center before dividing by zoom (`rotated` in `fs_main`)
+/// `y` grows downward, as in the framebuffer
+pub fn pixel_offset(params: &FractalParams, x: f32, y: f32, width: u32, height: u32) -> Vec2 {
+    let uv = Vec2::new(
+        ((x + 0.5) / width as f32).clamp(0.0, 1.0),
+        ((y + 0.5) / height as f32).clamp(0.0, 1.0),
+    );
+
+    let aspect = params.resolution[0] / params.resolution[1];
+    let ndc = Vec2::new(
+        (uv.x - 0.5) * 2.0 * aspect + params.ui_offset,
+        (uv.y - 0.5) * 2.0 + params.ui_offset_y,
+    );
+
+    let (rot_sin, rot_cos) = params.rotation.sin_cos();
+    Vec2::new(
+        ndc.x * rot_cos - ndc.y * rot_sin,
+        ndc.x * rot_sin + ndc.y * rot_cos,
+    )
+}
+
+/// Map a pixel center to the complex plane, matching the UV mapping in `fs_main`
+pub fn pixel_to_complex(params: &FractalParams, x: f32, y: f32, width: u32, height: u32) -> Vec2 {
+    Vec2::from(params.center_words().0) + pixel_offset(params, x, y, width, height) / params.shader_zoom()
+}
+
+/// Turn an iteration result into a linear RGB color, matching the tail of `fs_main`
+/// `root` is the Newton root reached as a fraction of a turn (-1 for none)
+pub fn shade(params: &FractalParams, result: Vec2, root: f32) -> palette::Rgb {
+    let iter = result.x;
+    let z_mag2 = result.y;
+
+    if iter >= params.max_iter as f32 {
+        // Point is in the set
+        return [0.0, 0.0, 0.0];
+    }
+
+    let mut t = if params.smooth() {
+        let log_zn = z_mag2.max(1.0).ln() / 2.0;
+        let nu = (log_zn / 2.0_f32.ln()).max(1e-10).ln() / params.power.ln();
+        (iter + 1.0 - nu) / params.max_iter as f32
+    } else {
+        iter / params.max_iter as f32
+    };
+
+    if params.offset() {
+        t = palette::fract(t * 5.0);
+    }
+
+    if params.invert() {
+        t = 1.0 - t;
+    }
+
+    if params.basins() && params.get_fractal_type() == FractalType::Newton {
+        palette::basin_color(root, t)
+    } else {
+        palette::get_color(t, ColorScheme::from_u32(params.color_scheme))
+    }
+}
+
+/// Iterate a single pixel the way `fs_main` picks its path
+/// Returns (iteration count, |z|^2) and the Newton root reached as a fraction of a turn (-1 for none);
+/// `roots` is [`NewtonRoots::new`] of `params`, and `reference` is the orbit at the view center and
+/// its uniform, required when `params.perturbation()`
+pub fn iterate_pixel(
+    params: &FractalParams,
+    roots: &NewtonRoots,
+    reference: Option<(&ReferenceOrbit, &PerturbationParams)>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> (Vec2, f32) {
+    if let Some((reference, uniform)) = reference.filter(|_| params.perturbation()) {
+        let result = perturbation::iterate_perturbed(
+            params.get_fractal_type(),
+            &reference.points,
+            uniform,
+            pixel_offset(params, x as f32, y as f32, width, height),
+            params.max_iter,
+            params.escape_radius,
+        );
+        return (result, -1.0);
+    }
+
+    if params.high_precision() {
+        let c = double_single::pixel_to_complex_ds(params, x as f32, y as f32, width, height);
+        return (double_single::iterate_ds(params, c), -1.0);
+    }
+
+    let c = pixel_to_complex(params, x as f32, y as f32, width, height);
+    iterate::iterate(params, roots, c)
+}
+
+/// Compute the linear RGB color of a single pixel
+/// `roots` and `reference` are as for [`iterate_pixel`]
+pub fn sample(
+    params: &FractalParams,
+    roots: &NewtonRoots,
+    reference: Option<(&ReferenceOrbit, &PerturbationParams)>,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> palette::Rgb {
+    let (result, root) = iterate_pixel(params, roots, reference, x, y, width, height);
+    shade(params, result, root)
+}
+
+/// Encode a linear channel value the way an sRGB render target does
+pub fn linear_to_srgb8(v: f32) -> u8 {
+    let v = v.clamp(0.0, 1.0);
+    let s = if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0 + 0.5) as u8
+}
+
+/// Render a full frame into a tightly packed RGBA8 (sRGB) buffer
+/// `params.resolution` should match `width`/`height` for the aspect ratio to agree with the GPU
+pub fn render(params: &FractalParams, width: u32, height: u32) -> Vec<u8> {
+    let width = width.max(1);
+    let height = height.max(1);
+    let row_bytes = width as usize * 4;
+    let mut pixels = vec![0u8; row_bytes * height as usize];
+    let reference = params.perturbation().then(|| {
+        let orbit = ReferenceOrbit::new(params);
+        let uniform = orbit.uniform(params);
+        (orbit, uniform)
+    });
+    let reference = reference.as_ref().map(|(orbit, uniform)| (orbit, uniform));
+    let roots = &NewtonRoots::new(params);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let rows_per_chunk = (height as usize).div_ceil(threads).max(1);
+
+        std::thread::scope(|scope| {
+            for (chunk_index, chunk) in pixels.chunks_mut(rows_per_chunk * row_bytes).enumerate() {
+                let first_row = (chunk_index * rows_per_chunk) as u32;
+                scope.spawn(move || render_rows(params, roots, reference, width, height, first_row, chunk));
+            }
+        });
+    }
+
+    // No threads on the web; the CPU path there is a last-resort fallback anyway
+    #[cfg(target_arch = "wasm32")]
+    render_rows(params, roots, reference, width, height, 0, &mut pixels);
+
+    pixels
+}
+
+fn render_rows(
+    params: &FractalParams,
+    roots: &NewtonRoots,
+    reference: Option<(&ReferenceOrbit, &PerturbationParams)>,
+    width: u32,
+    height: u32,
+    first_row: u32,
+    out: &mut [u8],
+) {
+    for (row_offset, row) in out.chunks_mut(width as usize * 4).enumerate() {
+        let y = first_row + row_offset as u32;
+        for (x, px) in row.chunks_mut(4).enumerate() {
+            let [r, g, b] = sample(params, roots, reference, x as u32, y, width, height);
+            px[0] = linear_to_srgb8(r);
+            px[1] = linear_to_srgb8(g);
+            px[2] = linear_to_srgb8(b);
+            px[3] = 255;
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! CPU mirror of the palette functions and `get_color` in `shaders/fractal.wgsl`
+//! Colors are returned in linear space, exactly as the fragment shader outputs them
+
+use std::f32::consts::PI;
+
+use crate::color::ColorScheme;
+
+pub type Rgb = [f32; 3];
+
+/// WGSL `fract`: x - floor(x), which differs from `f32::fract` for negative values
+pub(crate) fn fract(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn step(edge: f32, x: f32) -> f32 {
+    if x < edge {
+        0.0
+    } else {
+        1.0
+    }
+}
+
+fn palette_classic(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (2.0 * PI * (t + 0.0)).cos(),
+        0.5 + 0.5 * (2.0 * PI * (t + 0.33)).cos(),
+        0.5 + 0.5 * (2.0 * PI * (t + 0.67)).cos(),
+    ]
+}
+
+fn palette_fire(t: f32) -> Rgb {
+    [
+        (t * 3.0).min(1.0),
+        (t * 3.0 - 1.0).clamp(0.0, 1.0),
+        (t * 3.0 - 2.0).max(0.0),
+    ]
+}
+
+fn palette_ocean(t: f32) -> Rgb {
+    [0.0, 0.3 + 0.4 * t, 0.5 + 0.5 * t]
+}
+
+fn palette_rainbow(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (2.0 * PI * t).sin(),
+        0.5 + 0.5 * (2.0 * PI * (t + 0.33)).sin(),
+        0.5 + 0.5 * (2.0 * PI * (t + 0.67)).sin(),
+    ]
+}
+
+fn palette_grayscale(t: f32) -> Rgb {
+    [t, t, t]
+}
+
+fn palette_electric(t: f32) -> Rgb {
+    [t, t * t, 1.0]
+}
+
+fn palette_neon(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (2.0 * PI * t * 2.0).sin(),
+        0.5 + 0.5 * (2.0 * PI * t * 3.0).sin(),
+        0.5 + 0.5 * (2.0 * PI * t * 5.0).sin(),
+    ]
+}
+
+fn palette_sunset(t: f32) -> Rgb {
+    let a = [0.5, 0.5, 0.5];
+    let b = [0.5, 0.5, 0.5];
+    let c = [1.0, 0.7, 0.4];
+    let d = [0.0, 0.15, 0.2];
+    std::array::from_fn(|i| a[i] + b[i] * (2.0 * PI * (c[i] * t + d[i])).cos())
+}
+
+fn palette_forest(t: f32) -> Rgb {
+    if t < 0.25 {
+        let s = t / 0.25;
+        [0.15 + 0.1 * s, 0.1 + 0.2 * s, 0.05 + 0.05 * s]
+    } else if t < 0.5 {
+        let s = (t - 0.25) / 0.25;
+        [0.25 - 0.1 * s, 0.3 + 0.35 * s, 0.1 + 0.1 * s]
+    } else if t < 0.75 {
+        let s = (t - 0.5) / 0.25;
+        [0.15 + 0.25 * s, 0.65 + 0.2 * s, 0.2 - 0.05 * s]
+    } else {
+        let s = (t - 0.75) / 0.25;
+        [0.4 + 0.4 * s, 0.85 + 0.15 * s, 0.15 + 0.35 * s]
+    }
+}
+
+fn palette_lava(t: f32) -> Rgb {
+    [(0.5 + t).min(1.0), (t - 0.3).max(0.0) * 1.5, 0.0]
+}
+
+fn palette_ice(t: f32) -> Rgb {
+    [0.7 + 0.3 * t, 0.85 + 0.15 * t, 1.0]
+}
+
+fn palette_plasma(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (3.0 * PI * t).sin(),
+        0.5 + 0.5 * (3.0 * PI * t + 2.094).sin(),
+        0.5 + 0.5 * (3.0 * PI * t + 4.188).sin(),
+    ]
+}
+
+fn palette_cosmic(t: f32) -> Rgb {
+    [0.1 + 0.4 * t.powf(0.5), 0.0 + 0.2 * t, 0.3 + 0.7 * t.powf(0.7)]
+}
+
+fn palette_autumn(t: f32) -> Rgb {
+    [0.8 + 0.2 * (PI * t).sin(), 0.3 + 0.4 * t, 0.1 + 0.1 * t]
+}
+
+fn palette_matrix(t: f32) -> Rgb {
+    let base = 0.1 + 0.9 * t;
+    let pulse = 0.5 + 0.5 * (t * 20.0 * PI).sin();
+    [
+        0.02 * pulse * t,
+        base * (0.7 + 0.3 * pulse),
+        0.05 + 0.15 * t.powf(2.0),
+    ]
+}
+
+fn palette_vintage(t: f32) -> Rgb {
+    [0.6 + 0.3 * t, 0.5 + 0.3 * t, 0.4 + 0.2 * t]
+}
+
+fn palette_candy(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (4.0 * PI * t).sin(),
+        0.5 + 0.5 * (4.0 * PI * t + 1.0).sin(),
+        0.5 + 0.5 * (4.0 * PI * t + 2.0).sin(),
+    ]
+}
+
+fn palette_metal(t: f32) -> Rgb {
+    let base = 0.3 + 0.7 * t;
+    [base, base * 0.9, base * 0.8]
+}
+
+fn palette_toxic(t: f32) -> Rgb {
+    let pulse = 0.5 + 0.5 * (t * 8.0 * PI).sin();
+    if t < 0.3 {
+        let s = t / 0.3;
+        [0.1 * s, 0.2 + 0.3 * s, 0.05 + 0.1 * s]
+    } else if t < 0.6 {
+        let s = (t - 0.3) / 0.3;
+        [0.1 + 0.3 * s * pulse, 0.5 + 0.4 * s, 0.15 + 0.15 * s]
+    } else if t < 0.85 {
+        let s = (t - 0.6) / 0.25;
+        [0.4 + 0.5 * s, 0.9 + 0.1 * s, 0.3 - 0.1 * s]
+    } else {
+        let s = (t - 0.85) / 0.15;
+        [0.9 + 0.1 * s, 1.0, 0.2 + 0.6 * s]
+    }
+}
+
+fn palette_aurora(t: f32) -> Rgb {
+    [
+        0.2 + 0.3 * (2.0 * PI * t).sin(),
+        0.5 + 0.5 * (2.0 * PI * t + 1.5).sin(),
+        0.3 + 0.5 * (2.0 * PI * t + 3.0).sin(),
+    ]
+}
+
+fn palette_desert(t: f32) -> Rgb {
+    [0.8 + 0.2 * t, 0.6 + 0.2 * t, 0.3 + 0.2 * t]
+}
+
+fn palette_deep_sea(t: f32) -> Rgb {
+    [0.0 + 0.1 * t, 0.1 + 0.3 * t, 0.3 + 0.5 * t]
+}
+
+fn palette_magma(t: f32) -> Rgb {
+    [
+        0.1 + 0.9 * t.powf(0.5),
+        0.0 + 0.5 * t.powf(1.5),
+        0.2 + 0.3 * t.powf(3.0),
+    ]
+}
+
+fn palette_bw_bands(t: f32) -> Rgb {
+    let v = step(0.5, fract(t * 10.0));
+    [v, v, v]
+}
+
+fn palette_psychedelic(t: f32) -> Rgb {
+    [
+        0.5 + 0.5 * (10.0 * PI * t).sin(),
+        0.5 + 0.5 * (10.0 * PI * t + 2.094).sin(),
+        0.5 + 0.5 * (10.0 * PI * t + 4.188).sin(),
+    ]
+}
+
+fn palette_thermal(t: f32) -> Rgb {
+    if t < 0.15 {
+        let s = t / 0.15;
+        [0.0, 0.0, s * 0.5]
+    } else if t < 0.3 {
+        let s = (t - 0.15) / 0.15;
+        [0.0, s * 0.8, 0.5 + s * 0.5]
+    } else if t < 0.45 {
+        let s = (t - 0.3) / 0.15;
+        [s * 0.5, 0.8 + s * 0.2, 1.0 - s * 0.5]
+    } else if t < 0.6 {
+        let s = (t - 0.45) / 0.15;
+        [0.5 + s * 0.5, 1.0, 0.5 - s * 0.5]
+    } else if t < 0.75 {
+        let s = (t - 0.6) / 0.15;
+        [1.0, 1.0 - s * 0.4, 0.0]
+    } else if t < 0.9 {
+        let s = (t - 0.75) / 0.15;
+        [1.0, 0.6 - s * 0.6, 0.0]
+    } else {
+        let s = (t - 0.9) / 0.1;
+        [1.0, s, s]
+    }
+}
+
+/// Newton basin coloring: each root gets its own hue (by its angle), darkening with `t`
+/// Points that did not reach a root (`root < 0`) are black
+pub fn basin_color(root: f32, t: f32) -> Rgb {
+    if root < 0.0 {
+        return [0.0, 0.0, 0.0];
+    }
+    let shade = (-3.0 * t).exp();
+    [1.0, 2.0 / 3.0, 1.0 / 3.0].map(|k| ((fract(root + k) * 6.0 - 3.0).abs() - 1.0).clamp(0.0, 1.0) * shade)
+}
+
+/// Look up the palette color for `t` (nominally 0..1)
+pub fn get_color(t: f32, scheme: ColorScheme) -> Rgb {
+    match scheme {
+        ColorScheme::Classic => palette_classic(t),
+        ColorScheme::Fire => palette_fire(t),
+        ColorScheme::Ocean => palette_ocean(t),
+        ColorScheme::Rainbow => palette_rainbow(t),
+        ColorScheme::Grayscale => palette_grayscale(t),
+        ColorScheme::Electric => palette_electric(t),
+        ColorScheme::Neon => palette_neon(t),
+        ColorScheme::Sunset => palette_sunset(t),
+        ColorScheme::Forest => palette_forest(t),
+        ColorScheme::Lava => palette_lava(t),
+        ColorScheme::Ice => palette_ice(t),
+        ColorScheme::Plasma => palette_plasma(t),
+        ColorScheme::Cosmic => palette_cosmic(t),
+        ColorScheme::Autumn => palette_autumn(t),
+        ColorScheme::Matrix => palette_matrix(t),
+        ColorScheme::Vintage => palette_vintage(t),
+        ColorScheme::Candy => palette_candy(t),
+        ColorScheme::Metal => palette_metal(t),
+        ColorScheme::Toxic => palette_toxic(t),
+        ColorScheme::Aurora => palette_aurora(t),
+        ColorScheme::Desert => palette_desert(t),
+        ColorScheme::DeepSea => palette_deep_sea(t),
+        ColorScheme::Magma => palette_magma(t),
+        ColorScheme::BWBands => palette_bw_bands(t),
+        ColorScheme::Psychedelic => palette_psychedelic(t),
+        ColorScheme::Thermal => palette_thermal(t),
+    }
+}
@@ -0,0 +1,138 @@
+//! CPU mirror of `iterate_perturbed` in `shaders/fractal.wgsl`
+//! A pixel's delta from the reference orbit is kept as `w * 2^-k`: `k` starts at the
+//! uniform's `scale_exp` and only shrinks, whenever `w` outgrows [`RESCALE_LIMIT`]
+
+use super::iterate::{cmul, Vec2};
+use crate::fractal::FractalType;
+use crate::perturbation::{PerturbationParams, SERIES_TERMS};
+
+/// Scaled deltas larger than this are shifted down so `w^2` stays finite
+pub const RESCALE_LIMIT: f32 = 4_294_967_296.0;
+
+/// 2^e for e in -126..=127, built from the exponent bits like the shader's `pow2`
+fn pow2(e: i32) -> f32 {
+    f32::from_bits(((e + 127) as u32) << 23)
+}
+
+/// `v * 2^e`, in steps so the scale factor itself never overflows
+/// (WGSL `ldexp` is missing from GLSL ES 3.0, so the shader does the same)
+pub fn mul_pow2(v: Vec2, e: i32) -> Vec2 {
+    let (mut v, mut e) = (v, e);
+    while e > 126 {
+        v = v * pow2(126);
+        e -= 126;
+    }
+    while e < -126 {
+        v = v * pow2(-126);
+        e += 126;
+    }
+    v * pow2(e)
+}
+
+/// `|c + d| - |c|` in scaled units, with `cs = c * 2^k` and `d` a scaled delta
+fn diffabs(c: f32, cs: f32, d: f32) -> f32 {
+    if c >= 0.0 {
+        if cs + d >= 0.0 {
+            d
+        } else {
+            -(2.0 * cs + d)
+        }
+    } else if cs + d > 0.0 {
+        2.0 * cs + d
+    } else {
+        -d
+    }
+}
+
+/// `f(Z + d) - f(Z)` in scaled units, for `d = w * 2^-k` and `zs = Z * 2^k`
+fn perturb_step(fractal_type: FractalType, z: Vec2, zs: Vec2, w: Vec2, k: i32) -> Vec2 {
+    match fractal_type {
+        FractalType::BurningShip => {
+            let a = diffabs(z.x, zs.x, w.x);
+            let b = diffabs(z.y, zs.y, w.y);
+            let (ax, ay) = (z.x.abs(), z.y.abs());
+            Vec2::new(2.0 * (ax * a - ay * b), 2.0 * (ax * b + a * ay))
+                + mul_pow2(Vec2::new(a * a - b * b, 2.0 * a * b), -k)
+        }
+        FractalType::Tricorn => {
+            let d = cmul(z, w) * 2.0 + mul_pow2(cmul(w, w), -k);
+            Vec2::new(d.x, -d.y)
+        }
+        _ => cmul(z, w) * 2.0 + mul_pow2(cmul(w, w), -k),
+    }
+}
+
+/// Scaled delta after `perturbation.skip` iterations from the series approximation
+fn eval_series(perturbation: &PerturbationParams, w0: Vec2) -> Vec2 {
+    (0..SERIES_TERMS).rev().fold(Vec2::default(), |w, t| {
+        let row = perturbation.series[t / 2];
+        let c = Vec2::new(row[2 * (t % 2)], row[2 * (t % 2) + 1]);
+        cmul(w + c, w0)
+    })
+}
+
+/// Escape-time iteration of the pixel at rotated NDC `offset` (delta `offset / zoom` from the
+/// view center, which is `perturbation.reference_offset` away from the reference) by perturbation around `orbit`, as produced by
+/// [`crate::perturbation::reference_orbit`]. Returns (iteration count, |z|^2) like the f32 routines
+pub fn iterate_perturbed(
+    fractal_type: FractalType,
+    orbit: &[[f32; 2]],
+    perturbation: &PerturbationParams,
+    offset: Vec2,
+    max_iter: u32,
+    escape_radius: f32,
+) -> Vec2 {
+    let escape2 = escape_radius * escape_radius;
+    let reference = |n: u32| Vec2::from(orbit[n as usize]);
+    let k0 = perturbation.scale_exp;
+    let w0 = offset * perturbation.pixel_scale + Vec2::from(perturbation.reference_offset);
+
+    let mut w = Vec2::default();
+    let mut k = k0;
+    // Position in the reference orbit; runs behind `i` once the pixel has been rebased
+    let mut n: u32 = 0;
+    let mut z = Vec2::default();
+    let mut i: u32 = 0;
+
+    // Start past the iterations the series approximation covers
+    if perturbation.skip > 0 {
+        w = eval_series(perturbation, w0);
+        k = perturbation.series_exp;
+        n = perturbation.skip;
+        i = n.min(max_iter);
+        z = reference(n) + mul_pow2(w, -k);
+    }
+
+    while i < max_iter {
+        let zr = reference(n);
+        w = perturb_step(fractal_type, zr, mul_pow2(zr, k), w, k) + mul_pow2(w0, k - k0);
+        n += 1;
+        i += 1;
+
+        if w.x.abs().max(w.y.abs()) > RESCALE_LIMIT && k > 0 {
+            let shift = k.min(32);
+            w = mul_pow2(w, -shift);
+            k -= shift;
+        }
+
+        let zr = reference(n);
+        z = zr + mul_pow2(w, -k);
+        if z.dot(z) >= escape2 {
+            break;
+        }
+
+        // Rebase onto Z_0 = 0 once the reference runs out, or when the pixel comes closer to 0
+        // than to the reference, where the delta would lose its precision (a glitch)
+        let zs = mul_pow2(zr, k) + w;
+        if n + 1 >= orbit.len() as u32 {
+            w = z;
+            k = 0;
+            n = 0;
+        } else if zs.dot(zs) < w.dot(w) {
+            w = zs;
+            n = 0;
+        }
+    }
+
+    Vec2::new(i as f32, z.dot(z))
+}
@@ -0,0 +1,128 @@
+//! Deep links: the view state encoded in the URL hash
+//! The hash holds the same `key=value` fields as the PNG parameter chunk, joined with `&`
+
+use crate::export;
+use crate::fractal::FractalParams;
+use crate::scene::Scene;
+
+/// Encode the view-defining fields of `params` as a URL hash, including the leading `#`
+pub fn encode(params: &FractalParams) -> String {
+    let fields: Vec<String> = export::params_to_text(params)
+        .lines()
+        .map(escape)
+        .collect();
+    format!("#{}", fields.join("&"))
+}
+
+/// Decode a hash written by [`encode`], with or without the leading `#`
+/// Returns `None` for empty, malformed or unsupported hashes so callers can keep their defaults;
+/// values pass the same validation as scene files
+pub fn decode(hash: &str) -> Option<FractalParams> {
+    let hash = hash.strip_prefix('#').unwrap_or(hash);
+    if hash.is_empty() {
+        return None;
+    }
+
+    let text: String = hash
+        .split('&')
+        .filter(|field| !field.is_empty())
+        .map(|field| unescape(field).map(|field| field + "\n"))
+        .collect::<Option<_>>()?;
+
+    match export::params_from_text(&text).and_then(|params| Scene::new(&params).validate()) {
+        Ok(params) => Some(params),
+        Err(e) => {
+            log::warn!("Ignoring URL hash: {}", e);
+            None
+        }
+    }
+}
+
+/// Percent-encode everything except unreserved characters and the `=` and `,` separators
+fn escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for byte in field.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'=' | b',' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn unescape(field: &str) -> Option<String> {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Read the view from the page's current URL hash
+#[cfg(target_arch = "wasm32")]
+pub fn read_location() -> Option<FractalParams> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    decode(&hash)
+}
+
+/// Keeps `location.hash` in step with the view, and the view in step with hash edits
+/// Writes use `history.replaceState`, so panning does not flood the back button,
+/// and are throttled because browsers rate-limit history updates
+#[cfg(target_arch = "wasm32")]
+pub struct HashSync {
+    last_hash: String,
+    last_write: web_time::Instant,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl HashSync {
+    pub fn new(params: &FractalParams) -> Self {
+        Self {
+            last_hash: encode(params),
+            last_write: web_time::Instant::now(),
+        }
+    }
+
+    /// Call once per frame; returns true if the user edited the hash and `params` was updated
+    pub fn sync(&mut self, params: &mut FractalParams) -> bool {
+        use crate::constants::deeplink::HASH_UPDATE_INTERVAL;
+
+        let Some(window) = web_sys::window() else { return false };
+
+        // A hash that differs from what we last wrote was typed or pasted by the user
+        let current = window.location().hash().unwrap_or_default();
+        if current != self.last_hash {
+            self.last_hash = current;
+            if let Some(loaded) = decode(&self.last_hash) {
+                *params = FractalParams {
+                    resolution: params.resolution,
+                    ui_offset: params.ui_offset,
+                    ui_offset_y: params.ui_offset_y,
+                    ..loaded
+                };
+                return true;
+            }
+        }
+
+        let hash = encode(params);
+        if hash != self.last_hash && self.last_write.elapsed() >= HASH_UPDATE_INTERVAL {
+            if let Ok(history) = window.history() {
+                let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&hash));
+            }
+            self.last_hash = hash;
+            self.last_write = web_time::Instant::now();
+        }
+        false
+    }
+}
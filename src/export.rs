@@ -0,0 +1,210 @@
+//! PNG export with the view parameters embedded in an iTXt chunk
+//! A saved image can be loaded back to restore the exact view it was taken from
+
+use crate::color::ColorScheme;
+use crate::constants::limits;
+use crate::fractal::{FractalParams, FractalType};
+use crate::headless::TileRenderer;
+
+/// iTXt keyword under which the parameters are stored
+pub const PARAMS_KEYWORD: &str = "fractal-madness";
+
+/// Version of the key=value parameter text
+const PARAMS_VERSION: u32 = 1;
+
+/// Serialize the view-defining fields of `params` as `key=value` lines
+/// Floats use Rust's shortest round-trip formatting and the center its exact decimal text,
+/// so parsing restores them bit for bit
+pub fn params_to_text(params: &FractalParams) -> String {
+    let mut fields = vec![
+        ("version", PARAMS_VERSION.to_string()),
+        ("fractal_type", params.get_fractal_type().name().to_string()),
+        ("center", format!("{},{}", params.center[0], params.center[1])),
+        ("zoom", params.zoom.to_string()),
+        ("max_iter", params.max_iter.to_string()),
+        ("power", params.power.to_string()),
+        ("escape_radius", params.escape_radius.to_string()),
+        ("color_scheme", ColorScheme::from_u32(params.color_scheme).name().to_string()),
+        ("julia_c", format!("{},{}", params.julia_c[0], params.julia_c[1])),
+        ("flags", params.flags.to_string()),
+        ("rotation", params.rotation.to_string()),
+    ];
+
+    if params.auto_iterations() {
+        fields.push(("iteration_bias", params.iteration_bias.to_string()));
+    }
+
+    // Newton settings only mean something for Newton, so other links stay short
+    if params.get_fractal_type() == FractalType::Newton {
+        fields.push(("relaxation", params.relaxation.to_string()));
+        if !params.roots().is_empty() {
+            let roots: Vec<String> = params.roots().iter().flatten().map(f32::to_string).collect();
+            fields.push(("roots", roots.join(",")));
+        }
+    }
+
+    fields
+        .iter()
+        .map(|(key, value)| format!("{key}={value}\n"))
+        .collect()
+}
+
+/// Parse text written by [`params_to_text`]
+/// Unknown keys are ignored and missing keys keep their defaults
+pub fn params_from_text(text: &str) -> Result<FractalParams, String> {
+    let mut params = FractalParams::default();
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("Malformed line: {line}"))?;
+
+        match key {
+            "version" => {
+                let version: u32 = parse_field(key, value)?;
+                if version > PARAMS_VERSION {
+                    return Err(format!("Unsupported parameter version {version}"));
+                }
+            }
+            "fractal_type" => {
+                let ft = FractalType::from_name(value)
+                    .ok_or_else(|| format!("Unknown fractal type: {value}"))?;
+                params.set_fractal_type(ft);
+            }
+            "center" => params.center = parse_pair(key, value)?,
+            "zoom" => params.zoom = parse_field(key, value)?,
+            "max_iter" => params.max_iter = parse_field(key, value)?,
+            "power" => params.power = parse_field(key, value)?,
+            "escape_radius" => params.escape_radius = parse_field(key, value)?,
+            "color_scheme" => {
+                let cs = ColorScheme::from_name(value)
+                    .ok_or_else(|| format!("Unknown color scheme: {value}"))?;
+                params.color_scheme = cs as u32;
+            }
+            "julia_c" => params.julia_c = parse_pair(key, value)?,
+            "flags" => params.flags = parse_field(key, value)?,
+            "rotation" => params.rotation = parse_field(key, value)?,
+            "relaxation" => params.relaxation = parse_field(key, value)?,
+            "iteration_bias" => params.iteration_bias = parse_field(key, value)?,
+            "roots" => params.set_roots(&parse_roots(key, value)?),
+            _ => log::debug!("Ignoring unknown parameter key: {}", key),
+        }
+    }
+
+    Ok(params)
+}
+
+fn parse_field<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid value for {key}: {value}"))
+}
+
+fn parse_pair<T: std::str::FromStr>(key: &str, value: &str) -> Result<[T; 2], String> {
+    let (a, b) = value
+        .split_once(',')
+        .ok_or_else(|| format!("Expected two values for {key}: {value}"))?;
+    Ok([parse_field(key, a)?, parse_field(key, b)?])
+}
+
+/// Parse a flat `x0,y0,x1,y1,...` root list
+fn parse_roots(key: &str, value: &str) -> Result<Vec<[f32; 2]>, String> {
+    let values: Vec<f32> = value
+        .split(',')
+        .map(|v| parse_field(key, v))
+        .collect::<Result<_, _>>()?;
+    if !values.len().is_multiple_of(2) || values.len() / 2 > limits::MAX_NEWTON_ROOTS {
+        return Err(format!("Expected up to {} value pairs for {key}: {value}", limits::MAX_NEWTON_ROOTS));
+    }
+    Ok(values.chunks(2).map(|pair| [pair[0], pair[1]]).collect())
+}
+
+/// PNG encoder for an RGBA8 image with `params` embedded in an iTXt chunk
+fn png_encoder<'a>(
+    bytes: &'a mut Vec<u8>,
+    params: &FractalParams,
+    width: u32,
+    height: u32,
+) -> Result<png::Encoder<'a, &'a mut Vec<u8>>, String> {
+    let mut encoder = png::Encoder::new(bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    encoder
+        .add_itxt_chunk(PARAMS_KEYWORD.to_string(), params_to_text(params))
+        .map_err(|e| format!("Failed to add parameter chunk: {e}"))?;
+    Ok(encoder)
+}
+
+/// Encode an RGBA8 buffer as PNG with `params` embedded in an iTXt chunk
+pub fn encode_png(params: &FractalParams, width: u32, height: u32, pixels: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    {
+        let mut writer = png_encoder(&mut bytes, params, width, height)?
+            .write_header()
+            .map_err(|e| format!("Failed to write PNG header: {e}"))?;
+        writer
+            .write_image_data(pixels)
+            .map_err(|e| format!("Failed to write PNG data: {e}"))?;
+    }
+    Ok(bytes)
+}
+
+/// Read the parameters embedded by [`encode_png`] from PNG bytes
+pub fn read_png_params(bytes: &[u8]) -> Result<FractalParams, String> {
+    let decoder = png::Decoder::new(bytes);
+    let reader = decoder
+        .read_info()
+        .map_err(|e| format!("Not a readable PNG: {e}"))?;
+    let info = reader.info();
+
+    let itxt = info
+        .utf8_text
+        .iter()
+        .filter(|chunk| chunk.keyword == PARAMS_KEYWORD)
+        .map(|chunk| chunk.get_text().map_err(|e| format!("Corrupt parameter chunk: {e}")));
+    let text = info
+        .uncompressed_latin1_text
+        .iter()
+        .filter(|chunk| chunk.keyword == PARAMS_KEYWORD)
+        .map(|chunk| Ok(chunk.text.clone()));
+
+    let text = itxt
+        .chain(text)
+        .next()
+        .ok_or_else(|| "PNG has no Fractal Madness parameters".to_string())??;
+
+    params_from_text(&text)
+}
+
+/// Render `params` offscreen and encode the result as a PNG with embedded parameters
+/// The image is rendered in tiles and streamed into the encoder band by band, so poster
+/// sizes far beyond the GPU texture limit only need memory for one row of tiles
+pub async fn render_png(params: &FractalParams, width: u32, height: u32) -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    let mut bytes = Vec::new();
+    {
+        let mut writer = png_encoder(&mut bytes, params, width, height)?
+            .write_header()
+            .map_err(|e| format!("Failed to write PNG header: {e}"))?;
+        let mut stream = writer
+            .stream_writer()
+            .map_err(|e| format!("Failed to start PNG data: {e}"))?;
+
+        let mut renderer = TileRenderer::new().await;
+        renderer
+            .render_bands(params, width, height, |band| {
+                stream
+                    .write_all(band)
+                    .map_err(|e| format!("Failed to write PNG data: {e}"))
+            })
+            .await?;
+
+        stream
+            .finish()
+            .map_err(|e| format!("Failed to finish PNG: {e}"))?;
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,226 @@
+//! Platform file access: plain files natively, downloads and file pickers on the web
+//! Settings documents live in the config directory natively and in `localStorage` on the web
+//! Results of asynchronous or background operations are queued and drained once per frame
+
+use std::sync::Mutex;
+
+/// Outcome of a file operation that finished outside the frame loop
+pub enum FileEvent {
+    /// A file chosen by the user (file picker or drag and drop)
+    Opened { name: String, bytes: Vec<u8> },
+    /// Human-readable result message for the status line
+    Status(String),
+}
+
+/// Shared so native background threads can report back to the frame loop
+static EVENTS: Mutex<Vec<FileEvent>> = Mutex::new(Vec::new());
+
+/// Queue an event for the next frame
+pub fn push_event(event: FileEvent) {
+    if let Ok(mut events) = EVENTS.lock() {
+        events.push(event);
+    }
+}
+
+/// Take all events queued since the last call
+pub fn drain_events() -> Vec<FileEvent> {
+    EVENTS
+        .lock()
+        .map(|mut events| std::mem::take(&mut *events))
+        .unwrap_or_default()
+}
+
+/// Lowercase extension of a file name, if any
+pub fn extension(name: &str) -> Option<String> {
+    std::path::Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Seconds since the Unix epoch, for unique file names
+pub fn timestamp() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Write `bytes` to `file_name` in the working directory, returning the full path
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_bytes(file_name: &str, bytes: &[u8], _mime: &str) -> Result<String, String> {
+    let path = std::env::current_dir()
+        .map_err(|e| format!("No working directory: {e}"))?
+        .join(file_name);
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path.display().to_string())
+}
+
+/// Read a file the user pointed at (path field or drag and drop) and queue it as opened
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open_path(path: &std::path::Path) {
+    match std::fs::read(path) {
+        Ok(bytes) => push_event(FileEvent::Opened {
+            name: path.display().to_string(),
+            bytes,
+        }),
+        Err(e) => push_event(FileEvent::Status(format!("Failed to read {}: {e}", path.display()))),
+    }
+}
+
+/// Per-user settings directory for this app, following each platform's convention
+#[cfg(not(target_arch = "wasm32"))]
+fn config_dir() -> Option<std::path::PathBuf> {
+    use std::path::PathBuf;
+
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        var("XDG_CONFIG_HOME").or_else(|| var("HOME").map(|home| home.join(".config")))
+    };
+    Some(base?.join("fractal-madness"))
+}
+
+/// Read the settings document `name` (a JSON file in the config directory)
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_config(name: &str) -> Option<String> {
+    std::fs::read_to_string(config_dir()?.join(format!("{name}.json"))).ok()
+}
+
+/// Replace the settings document `name`, creating the config directory if needed
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_config(name: &str, text: &str) -> Result<(), String> {
+    let dir = config_dir().ok_or("No config directory")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let path = dir.join(format!("{name}.json"));
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Offer `bytes` to the browser as a download named `file_name`
+#[cfg(target_arch = "wasm32")]
+pub fn save_bytes(file_name: &str, bytes: &[u8], mime: &str) -> Result<String, String> {
+    use wasm_bindgen::JsCast;
+
+    let document = web_sys::window()
+        .and_then(|win| win.document())
+        .ok_or("No document available")?;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|e| format!("Failed to create blob: {e:?}"))?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(|e| format!("Failed to create object URL: {e:?}"))?;
+
+    let anchor = document
+        .create_element("a")
+        .ok()
+        .and_then(|el| el.dyn_into::<web_sys::HtmlAnchorElement>().ok())
+        .ok_or("Failed to create download link")?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+
+    Ok(file_name.to_string())
+}
+
+/// Open the browser file picker; the chosen file arrives as [`FileEvent::Opened`]
+#[cfg(target_arch = "wasm32")]
+pub fn pick_file(accept: &str) {
+    use wasm_bindgen::JsCast;
+
+    let Some(input) = web_sys::window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.create_element("input").ok())
+        .and_then(|el| el.dyn_into::<web_sys::HtmlInputElement>().ok())
+    else {
+        log::warn!("Failed to create file input");
+        return;
+    };
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picker = input.clone();
+    let on_change = wasm_bindgen::closure::Closure::once_into_js(move || {
+        let Some(file) = picker.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let name = file.name();
+            match wasm_bindgen_futures::JsFuture::from(file.array_buffer()).await {
+                Ok(buffer) => push_event(FileEvent::Opened {
+                    name,
+                    bytes: js_sys::Uint8Array::new(&buffer).to_vec(),
+                }),
+                Err(e) => push_event(FileEvent::Status(format!("Failed to read {name}: {e:?}"))),
+            }
+        });
+    });
+    input.set_onchange(Some(on_change.unchecked_ref()));
+    input.click();
+}
+
+/// `url` without its query and fragment, so the extension of the file it names can be read
+#[cfg(target_arch = "wasm32")]
+fn url_path(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Download `url`; the body arrives as [`FileEvent::Opened`] named after the URL's path
+#[cfg(target_arch = "wasm32")]
+pub fn fetch_url(url: &str) {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let Some(window) = web_sys::window() else {
+        log::warn!("No window to fetch from");
+        return;
+    };
+    let request = window.fetch_with_str(url);
+    let url = url.to_string();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let body = async {
+            let response: web_sys::Response = JsFuture::from(request)
+                .await
+                .and_then(|response| response.dyn_into())
+                .map_err(|e| format!("Failed to fetch {url}: {e:?}"))?;
+            if !response.ok() {
+                return Err(format!("Failed to fetch {url}: HTTP {}", response.status()));
+            }
+            let buffer = JsFuture::from(response.array_buffer().map_err(|e| format!("Failed to read {url}: {e:?}"))?)
+                .await
+                .map_err(|e| format!("Failed to read {url}: {e:?}"))?;
+            Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+        };
+        match body.await {
+            Ok(bytes) => push_event(FileEvent::Opened { name: url_path(&url).to_string(), bytes }),
+            Err(e) => push_event(FileEvent::Status(e)),
+        }
+    });
+}
+
+/// Browser storage that survives reloads
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Read the settings document `name` from `localStorage`
+#[cfg(target_arch = "wasm32")]
+pub fn read_config(name: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("fractal-madness.{name}")).ok()?
+}
+
+/// Replace the settings document `name` in `localStorage`
+#[cfg(target_arch = "wasm32")]
+pub fn write_config(name: &str, text: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or("Browser storage is not available")?
+        .set_item(&format!("fractal-madness.{name}"), text)
+        .map_err(|e| format!("Failed to store {name}: {e:?}"))
+}
@@ -0,0 +1,336 @@
+//! Fixed-point reals for work that outgrows f64 (view centers and reference orbits of deep zooms)
+//! A `Fixed<N>` has one 32-bit integer limb and `N - 1` fraction limbs, so it resolves
+//! `2^-(32 * (N - 1))`. Limbs are little-endian with the sign kept apart, which keeps
+//! multiplication on plain magnitudes. Results truncate toward zero; the integer part wraps
+//! past 2^32, far beyond anything an escape radius lets through
+//!
+//! As text, values that are exactly an f64 are written like one; anything else is written as a
+//! decimal with more significant digits than an f64 ever needs, which is read back at full precision
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Magnitudes from here on do not fit the integer limb
+const LIMIT: f64 = 4_294_967_296.0;
+
+/// Decimal text with at most this many significant digits is read as an f64
+/// (the shortest text of any f64 has no more than 17)
+const F64_DIGITS: usize = 17;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fixed<const N: usize> {
+    negative: bool,
+    /// Magnitude, least significant limb first; `limbs[N - 1]` is the integer part
+    limbs: [u32; N],
+}
+
+impl<const N: usize> Default for Fixed<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const N: usize> Fixed<N> {
+    pub const ZERO: Self = Self { negative: false, limbs: [0; N] };
+
+    /// Bits after the binary point
+    pub const FRACTION_BITS: u32 = 32 * (N as u32 - 1);
+
+    fn new(negative: bool, limbs: [u32; N]) -> Self {
+        // Zero is never negative, so equal values compare equal
+        let negative = negative && limbs.iter().any(|&l| l != 0);
+        Self { negative, limbs }
+    }
+
+    /// Exact conversion of a finite f64 with magnitude below 2^32 (bits past the last limb are dropped)
+    pub fn from_f64(v: f64) -> Self {
+        let mut limbs = [0; N];
+        let mut rest = v.abs();
+        for limb in limbs.iter_mut().rev() {
+            let whole = rest.floor();
+            *limb = whole as u32;
+            rest = (rest - whole) * 4_294_967_296.0;
+        }
+        Self::new(v < 0.0, limbs)
+    }
+
+    /// Like [`Self::from_f64`], but rejects values that are not finite or do not fit
+    pub fn try_from_f64(v: f64) -> Result<Self, String> {
+        if v.is_finite() && v.abs() < LIMIT {
+            Ok(Self::from_f64(v))
+        } else {
+            Err(format!("{v} is out of range"))
+        }
+    }
+
+    /// Nearest f64 (to within an ulp)
+    pub fn to_f64(&self) -> f64 {
+        // Smallest limbs first so they are not lost against the large ones
+        let magnitude = self
+            .limbs
+            .iter()
+            .enumerate()
+            .fold(0.0, |acc, (i, &limb)| acc + limb as f64 * 2.0_f64.powi(32 * (i as i32 - (N as i32 - 1))));
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// The same value with `M` limbs, dropping fraction limbs or padding with zeros
+    pub fn resize<const M: usize>(&self) -> Fixed<M> {
+        let mut limbs = [0; M];
+        for (to, from) in limbs.iter_mut().rev().zip(self.limbs.iter().rev()) {
+            *to = *from;
+        }
+        Fixed::new(self.negative, limbs)
+    }
+
+    /// The value as an f64, if it is exactly one
+    pub fn to_exact_f64(&self) -> Option<f64> {
+        let v = self.to_f64();
+        (Self::from_f64(v) == *self).then_some(v)
+    }
+
+    /// Decimal digits after the point that tell any two values apart
+    const DECIMAL_DIGITS: usize = (Self::FRACTION_BITS as usize * 30_103).div_ceil(100_000) + 1;
+
+    /// Decimal text with exactly `digits` digits after the point, rounded half up
+    pub fn to_decimal(&self, digits: usize) -> String {
+        let mut fraction = self.limbs;
+        let mut whole = self.limbs[N - 1] as u64;
+        // One digit past the last, to round on
+        let mut out = Vec::with_capacity(digits + 1);
+        for _ in 0..=digits {
+            // Multiplying the fraction by ten carries its next digit out of the top fraction limb
+            let mut carry = 0;
+            for limb in &mut fraction[..N - 1] {
+                let v = *limb as u64 * 10 + carry;
+                *limb = v as u32;
+                carry = v >> 32;
+            }
+            out.push(carry as u8);
+        }
+
+        if out.pop().is_some_and(|last| last >= 5) {
+            // The trailing nines turn into zeros and carry into the digit before them
+            let last = out.iter().rposition(|&d| d != 9);
+            match last {
+                Some(i) => out[i] += 1,
+                None => whole += 1,
+            }
+            out[last.map_or(0, |i| i + 1)..].fill(0);
+        }
+
+        let zero = whole == 0 && out.iter().all(|&d| d == 0);
+        let sign = if self.negative && !zero { "-" } else { "" };
+        let mut text = format!("{sign}{whole}");
+        if digits > 0 {
+            text.push('.');
+            text.extend(out.iter().map(|&d| char::from(b'0' + d)));
+        }
+        text
+    }
+
+    pub fn abs(self) -> Self {
+        Self::new(false, self.limbs)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+fn cmp_magnitude<const N: usize>(a: &[u32; N], b: &[u32; N]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+fn add_magnitude<const N: usize>(a: &[u32; N], b: &[u32; N]) -> [u32; N] {
+    let mut out = [0; N];
+    let mut carry = 0u64;
+    for i in 0..N {
+        let sum = a[i] as u64 + b[i] as u64 + carry;
+        out[i] = sum as u32;
+        carry = sum >> 32;
+    }
+    out
+}
+
+/// `a - b`, given `a >= b`
+fn sub_magnitude<const N: usize>(a: &[u32; N], b: &[u32; N]) -> [u32; N] {
+    let mut out = [0; N];
+    let mut borrow = 0u64;
+    for i in 0..N {
+        let (diff, under) = (a[i] as u64).overflowing_sub(b[i] as u64 + borrow);
+        out[i] = diff as u32;
+        borrow = under as u64;
+    }
+    out
+}
+
+impl<const N: usize> Add for Fixed<N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        if self.negative == rhs.negative {
+            return Self::new(self.negative, add_magnitude(&self.limbs, &rhs.limbs));
+        }
+        match cmp_magnitude(&self.limbs, &rhs.limbs) {
+            Ordering::Greater => Self::new(self.negative, sub_magnitude(&self.limbs, &rhs.limbs)),
+            Ordering::Less => Self::new(rhs.negative, sub_magnitude(&rhs.limbs, &self.limbs)),
+            Ordering::Equal => Self::ZERO,
+        }
+    }
+}
+
+impl<const N: usize> Neg for Fixed<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(!self.negative, self.limbs)
+    }
+}
+
+impl<const N: usize> Sub for Fixed<N> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const N: usize> Mul for Fixed<N> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        // Schoolbook product, one column at a time; columns below N - 1 only feed the carry
+        let (a, b) = (&self.limbs, &rhs.limbs);
+        let mut limbs = [0; N];
+        let mut carry: u128 = 0;
+        for column in 0..2 * N - 1 {
+            let mut sum = carry;
+            for i in column.saturating_sub(N - 1)..=column.min(N - 1) {
+                sum += (a[i] as u64 * b[column - i] as u64) as u128;
+            }
+            if column >= N - 1 {
+                limbs[column - (N - 1)] = sum as u32;
+            }
+            carry = sum >> 32;
+        }
+        Self::new(self.negative != rhs.negative, limbs)
+    }
+}
+
+/// Digits of a decimal mantissa, not counting leading zeros
+fn significant_digits(mantissa: &str) -> usize {
+    mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .skip_while(|&d| d == b'0')
+        .count()
+}
+
+/// Multiply the decimal fraction `0.d0 d1 d2 ...` by `factor` in place and return the integer part
+fn shift_out(fraction: &mut [u8], factor: u64) -> u64 {
+    let mut carry = 0;
+    for d in fraction.iter_mut().rev() {
+        let v = *d as u64 * factor + carry;
+        *d = (v % 10) as u8;
+        carry = v / 10;
+    }
+    carry
+}
+
+impl<const N: usize> FromStr for Fixed<N> {
+    type Err = String;
+
+    /// Read decimal text (with an optional exponent), rounding to the nearest value
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid number: {s}");
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((m, e)) => (m, e.parse::<i32>().map_err(|_| invalid())?),
+            None => (unsigned, 0),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits: Vec<u8> = int.bytes().chain(frac.bytes()).map(|b| b.wrapping_sub(b'0')).collect();
+        if digits.is_empty() || digits.iter().any(|&d| d > 9) {
+            return Err(invalid());
+        }
+
+        if significant_digits(mantissa) <= F64_DIGITS {
+            return s.parse::<f64>().map_err(|_| invalid()).and_then(Self::try_from_f64);
+        }
+
+        // Digit `i` of the value counted from the decimal point, 0 being the first after it
+        let point = int.len() as i64 + exponent as i64;
+        let digit = |i: i64| usize::try_from(point + i).ok().and_then(|i| digits.get(i)).copied().unwrap_or(0);
+        let first = digits.iter().position(|&d| d != 0).unwrap_or(0) as i64;
+        if point - first > 10 {
+            return Err(format!("{s} is out of range"));
+        }
+        let whole = (first - point..0).fold(0u64, |acc, i| acc * 10 + digit(i) as u64);
+        if whole >= u32::MAX as u64 {
+            return Err(format!("{s} is out of range"));
+        }
+
+        // One more digit than bits resolves the halfway point exactly, which is all rounding needs
+        let mut fraction: Vec<u8> = (0..=Self::FRACTION_BITS as i64).map(digit).collect();
+        let mut limbs = [0; N];
+        limbs[N - 1] = whole as u32;
+        for limb in limbs[..N - 1].iter_mut().rev() {
+            *limb = shift_out(&mut fraction, 1 << 32) as u32;
+        }
+        if shift_out(&mut fraction, 2) == 1 {
+            let mut ulp = [0; N];
+            ulp[0] = 1;
+            limbs = add_magnitude(&limbs, &ulp);
+        }
+        Ok(Self::new(negative, limbs))
+    }
+}
+
+impl<const N: usize> fmt::Display for Fixed<N> {
+    /// Shortest text that reads back to the same value
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(v) = self.to_exact_f64() {
+            return write!(f, "{v}");
+        }
+        let text = (0..=Self::DECIMAL_DIGITS)
+            .map(|digits| self.to_decimal(digits))
+            .find(|text| significant_digits(text) > F64_DIGITS && text.parse() == Ok(*self))
+            .unwrap_or_else(|| self.to_decimal(Self::DECIMAL_DIGITS));
+        f.write_str(&text)
+    }
+}
+
+/// Numbers when the value is exactly an f64, decimal strings otherwise
+impl<const N: usize> Serialize for Fixed<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.to_exact_f64() {
+            Some(v) => serializer.serialize_f64(v),
+            None => serializer.collect_str(self),
+        }
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Fixed<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Text {
+            Number(f64),
+            Decimal(String),
+        }
+
+        match Text::deserialize(deserializer)? {
+            Text::Number(v) => Self::try_from_f64(v),
+            Text::Decimal(s) => s.parse(),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
@@ -1,17 +1,14 @@
 use bytemuck::{Pod, Zeroable};
-use crate::constants::canvas;
+use serde::{Deserialize, Serialize};
+use crate::constants::{canvas, limits, precision, ui};
+use crate::cpu::NewtonRoots;
+use crate::fixed::Fixed;
 
-pub mod burning_ship;
-pub mod buffalo;
-pub mod celtic;
-pub mod julia;
-pub mod mandelbrot;
-pub mod newton;
-pub mod phoenix;
-pub mod tricorn;
+/// Exact coordinate of a view center
+pub type Coordinate = Fixed<{ precision::CENTER_LIMBS }>;
 
 /// Fractal type enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum FractalType {
     // Classic escape-time fractals
@@ -60,6 +57,11 @@ impl FractalType {
         }
     }
 
+    /// Look up a fractal type by its display name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|ft| ft.name() == name)
+    }
+
     /// Returns true if this fractal type uses the julia_c parameter
     pub fn needs_julia_c(&self) -> bool {
         matches!(
@@ -87,13 +89,44 @@ impl FractalType {
     }
 }
 
-/// Fractal rendering parameters
+/// Fractal view parameters
+/// The center is kept exactly; the shader gets the rounded [`FractalUniform`] derived from it
+/// Serializes only the view-defining fields; per-frame layout fields take their defaults
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FractalParams {
+    pub center: [Coordinate; 2],
+    pub zoom: f64,
+    pub max_iter: u32,
+    pub power: f32,
+    pub escape_radius: f32,
+    #[serde(with = "serde_fractal_type")]
+    pub fractal_type: u32,
+    #[serde(with = "serde_color_scheme")]
+    pub color_scheme: u32,
+    pub julia_c: [f32; 2],
+    pub flags: u32,
+    pub rotation: f32,           // view rotation in radians
+    #[serde(skip)]
+    pub resolution: [f32; 2],
+    #[serde(skip)]
+    pub ui_offset: f32,          // horizontal offset for UI panel
+    #[serde(skip)]
+    pub ui_offset_y: f32,        // vertical offset for centering
+    #[serde(skip_serializing_if = "no_roots")]
+    pub newton_roots: [[f32; 2]; limits::MAX_NEWTON_ROOTS], // Newton polynomial roots
+    pub root_count: u32,         // roots in use; 0 iterates z^n - 1
+    pub relaxation: f32,         // Newton step factor a
+    pub iteration_bias: f32,     // factor on the automatic iteration count
+}
+
+/// Fractal rendering parameters as the shader sees them
 /// Must match the WGSL struct layout exactly and stay 16-byte aligned for uniforms
-/// Total size: 64 bytes
+/// Total size: 192 bytes
 #[repr(C, align(16))]
 #[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
-pub struct FractalParams {
-    pub center: [f32; 2],       // offset 0  (8 bytes)
+pub struct FractalUniform {
+    pub center: [f32; 2],        // offset 0  (8 bytes)
     pub zoom: f32,               // offset 8  (4 bytes)
     pub max_iter: u32,           // offset 12 (4 bytes)
     pub power: f32,              // offset 16 (4 bytes)
@@ -102,16 +135,62 @@ pub struct FractalParams {
     pub color_scheme: u32,       // offset 28 (4 bytes)
     pub julia_c: [f32; 2],       // offset 32 (8 bytes)
     pub flags: u32,              // offset 40 (4 bytes)
-    pub _pad: u32,               // offset 44 (4 bytes)
+    pub rotation: f32,           // offset 44 (4 bytes) - view rotation in radians
     pub resolution: [f32; 2],    // offset 48 (8 bytes)
     pub ui_offset: f32,          // offset 56 (4 bytes) - horizontal offset for UI panel
     pub ui_offset_y: f32,        // offset 60 (4 bytes) - vertical offset for centering
+    pub newton_roots: [[f32; 2]; limits::MAX_NEWTON_ROOTS], // offset 64 (64 bytes) - Newton polynomial roots
+    pub root_count: u32,         // offset 128 (4 bytes) - roots in use; 0 iterates z^n - 1
+    pub relaxation: f32,         // offset 132 (4 bytes) - Newton step factor a
+    pub center_lo: [f32; 2],     // offset 136 (8 bytes) - low words of the center (center + center_lo)
+    pub root_turns: [f32; limits::MAX_NEWTON_ROOTS], // offset 144 (32 bytes) - value reported for reaching each root
+    pub unity_root_count: u32,   // offset 176 (4 bytes) - roots of z^n = 1 in newton_roots while root_count is 0
+    pub _padding: [u32; 3],      // offset 180 (12 bytes)
+}
+
+/// True when no custom Newton roots are set, so scenes without them stay short
+fn no_roots(roots: &[[f32; 2]; limits::MAX_NEWTON_ROOTS]) -> bool {
+    roots.iter().all(|root| *root == [0.0, 0.0])
+}
+
+/// Stores `fractal_type` by variant name rather than shader index
+mod serde_fractal_type {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{FractalParams, FractalType};
+
+    pub fn serialize<S: Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        let params = FractalParams {
+            fractal_type: *value,
+            ..FractalParams::default()
+        };
+        params.get_fractal_type().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        Ok(FractalType::deserialize(deserializer)? as u32)
+    }
+}
+
+/// Stores `color_scheme` by variant name rather than shader index
+mod serde_color_scheme {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::color::ColorScheme;
+
+    pub fn serialize<S: Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+        ColorScheme::from_u32(*value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+        Ok(ColorScheme::deserialize(deserializer)? as u32)
+    }
 }
 
 impl Default for FractalParams {
     fn default() -> Self {
         Self {
-            center: [-0.5, 0.0],
+            center: [-0.5, 0.0].map(Coordinate::from_f64),
             zoom: 1.0,
             max_iter: 256,
             power: 2.0,
@@ -120,10 +199,14 @@ impl Default for FractalParams {
             color_scheme: 0,
             julia_c: [-0.7, 0.27015],
             flags: 1, // smooth coloring on by default
-            _pad: 0,
+            rotation: 0.0,
             resolution: [canvas::DEFAULT_WIDTH as f32, canvas::WASM_WINDOW_HEIGHT as f32], // default, will be updated each frame
             ui_offset: 0.0,              // will be updated each frame based on UI panel width
             ui_offset_y: 0.0,            // will be updated each frame for vertical centering
+            newton_roots: [[0.0; 2]; limits::MAX_NEWTON_ROOTS],
+            root_count: 0,
+            relaxation: 1.0,
+            iteration_bias: 1.0,
         }
     }
 }
@@ -133,6 +216,10 @@ impl FractalParams {
     pub const FLAG_SMOOTH: u32 = 1;
     pub const FLAG_INVERT: u32 = 2;
     pub const FLAG_OFFSET: u32 = 4;
+    /// Newton only: color by the root reached instead of the palette
+    pub const FLAG_BASINS: u32 = 8;
+    /// Derive `max_iter` from the view instead of the slider; not read by the shader
+    pub const FLAG_AUTO_ITERATIONS: u32 = 16;
 
     pub fn get_fractal_type(&self) -> FractalType {
         match self.fractal_type {
@@ -189,17 +276,136 @@ impl FractalParams {
         }
     }
 
-    pub fn pan(&mut self, dx: f32, dy: f32, width: f32, height: f32) {
-        // Convert pixel delta to normalized coordinates, then to complex plane
-        let scale = 2.0 / self.zoom;
-        let aspect = width / height;
-        self.center[0] -= (dx / width) * scale * aspect * 2.0;
-        self.center[1] -= (dy / height) * scale * 2.0;
+    pub fn basins(&self) -> bool {
+        (self.flags & Self::FLAG_BASINS) != 0
+    }
+
+    pub fn set_basins(&mut self, v: bool) {
+        if v {
+            self.flags |= Self::FLAG_BASINS;
+        } else {
+            self.flags &= !Self::FLAG_BASINS;
+        }
+    }
+
+    pub fn auto_iterations(&self) -> bool {
+        (self.flags & Self::FLAG_AUTO_ITERATIONS) != 0
+    }
+
+    pub fn set_auto_iterations(&mut self, v: bool) {
+        if v {
+            self.flags |= Self::FLAG_AUTO_ITERATIONS;
+        } else {
+            self.flags &= !Self::FLAG_AUTO_ITERATIONS;
+        }
+    }
+
+    /// The view center, rounded to f64
+    pub fn center_f64(&self) -> [f64; 2] {
+        self.center.map(|c| c.to_f64())
+    }
+
+    /// Move the view center to exactly `center`
+    pub fn set_center(&mut self, center: [f64; 2]) {
+        self.center = center.map(Coordinate::from_f64);
+    }
+
+    /// The center rounded to f32 high words and the f32 low words of the rest,
+    /// for the shader's double-single math
+    pub fn center_words(&self) -> ([f32; 2], [f32; 2]) {
+        let center = self.center_f64();
+        let hi = center.map(|v| v as f32);
+        (hi, [(center[0] - hi[0] as f64) as f32, (center[1] - hi[1] as f64) as f32])
+    }
+
+    /// The shader uniform for these parameters
+    /// The Newton roots, custom or of z^n = 1, are worked out here so the shader does not per pixel
+    pub fn uniform(&self) -> FractalUniform {
+        let (center, center_lo) = self.center_words();
+        let roots = NewtonRoots::new(self);
+        let mut newton_roots = [[0.0; 2]; limits::MAX_NEWTON_ROOTS];
+        let mut root_turns = [0.0; limits::MAX_NEWTON_ROOTS];
+        for (i, (point, turn)) in roots.iter().enumerate().take(limits::MAX_NEWTON_ROOTS) {
+            newton_roots[i] = [point.x, point.y];
+            root_turns[i] = *turn;
+        }
+        FractalUniform {
+            center,
+            zoom: self.shader_zoom(),
+            max_iter: self.max_iter,
+            power: self.power,
+            escape_radius: self.escape_radius,
+            fractal_type: self.fractal_type,
+            color_scheme: self.color_scheme,
+            julia_c: self.julia_c,
+            flags: self.flags,
+            rotation: self.rotation,
+            resolution: self.resolution,
+            ui_offset: self.ui_offset,
+            ui_offset_y: self.ui_offset_y,
+            newton_roots,
+            root_count: self.root_count,
+            relaxation: self.relaxation,
+            center_lo,
+            root_turns,
+            unity_root_count: if self.root_count == 0 { roots.len() as u32 } else { 0 },
+            _padding: [0; 3],
+        }
+    }
+
+    /// The zoom as the shader sees it: rounded to f32 and kept finite past the f32 range,
+    /// where only perturbation, which scales by [`crate::perturbation::PerturbationParams`], resolves pixels
+    pub fn shader_zoom(&self) -> f32 {
+        self.zoom.min(f32::MAX as f64) as f32
+    }
+
+    /// Whether the shader iterates this view in double-single precision
+    /// Mirrors the check in `fs_main`: only the power-2 escape-time formulas have a
+    /// double-single path, and it is used once pixels get too small for f32
+    pub fn high_precision(&self) -> bool {
+        let pixel_size = 2.0 / (self.resolution[1] * self.shader_zoom());
+        let supported = !matches!(self.get_fractal_type(), FractalType::Newton | FractalType::Phoenix);
+        supported && self.power == 2.0 && pixel_size < precision::HIGH_PRECISION_PIXEL_SIZE
+    }
+
+    /// Whether this view is rendered by perturbation against a reference orbit (see
+    /// [`crate::perturbation`]); takes over from double-single for Mandelbrot, Burning Ship and Tricorn
+    pub fn perturbation(&self) -> bool {
+        self.perturbation_at(self.resolution[1])
+    }
+
+    /// [`Self::perturbation`] on a canvas `height` pixels tall instead of the current one
+    pub fn perturbation_at(&self, height: f32) -> bool {
+        let pixel_size = 2.0 / (height * self.shader_zoom());
+        let supported = matches!(
+            self.get_fractal_type(),
+            FractalType::Mandelbrot | FractalType::BurningShip | FractalType::Tricorn
+        );
+        supported && self.power == 2.0 && pixel_size < precision::PERTURBATION_PIXEL_SIZE
+    }
+
+    /// Highest iteration count the slider and scene files allow for this view:
+    /// perturbation lifts the limit, since its deep zooms need far more
+    pub fn max_iterations(&self) -> u32 {
+        if self.perturbation() {
+            ui::MAX_DEEP_ITERATIONS as u32
+        } else {
+            ui::MAX_ITERATIONS as u32
+        }
+    }
+
+    /// Custom Newton roots in use; empty when Newton iterates z^n - 1
+    pub fn roots(&self) -> &[[f32; 2]] {
+        &self.newton_roots[..(self.root_count as usize).min(limits::MAX_NEWTON_ROOTS)]
     }
 
-    pub fn zoom_by(&mut self, delta: f32) {
-        self.zoom *= (1.0 + delta).max(0.1);
-        self.zoom = self.zoom.clamp(1e-10, 1e10);
+    /// Use `roots` (at most [`limits::MAX_NEWTON_ROOTS`]) as the Newton polynomial roots
+    /// Unused slots are zeroed so equal polynomials compare equal
+    pub fn set_roots(&mut self, roots: &[[f32; 2]]) {
+        let count = roots.len().min(limits::MAX_NEWTON_ROOTS);
+        self.newton_roots = [[0.0; 2]; limits::MAX_NEWTON_ROOTS];
+        self.newton_roots[..count].copy_from_slice(&roots[..count]);
+        self.root_count = count as u32;
     }
 
     pub fn reset(&mut self) {
@@ -215,31 +421,31 @@ impl FractalParams {
         // Apply fractal-specific defaults
         match self.get_fractal_type() {
             FractalType::Mandelbrot => {
-                self.center = [-0.5, 0.0];
+                self.set_center([-0.5, 0.0]);
             }
             FractalType::Julia => {
-                self.center = [0.0, 0.0];
+                self.set_center([0.0, 0.0]);
             }
             FractalType::BurningShip => {
-                self.center = [-0.4, -0.6];
+                self.set_center([-0.4, -0.6]);
             }
             FractalType::Tricorn => {
-                self.center = [-0.3, 0.0];
+                self.set_center([-0.3, 0.0]);
             }
             FractalType::Celtic => {
-                self.center = [-0.5, 0.0];
+                self.set_center([-0.5, 0.0]);
             }
             FractalType::BuffaloJulia
             | FractalType::CelticJulia => {
-                self.center = [0.0, 0.0];
+                self.set_center([0.0, 0.0]);
             }
             FractalType::Newton => {
-                self.center = [0.0, 0.0];
+                self.set_center([0.0, 0.0]);
                 self.zoom = 0.8;
                 self.max_iter = 15;  // Newton is expensive, start low
             }
             FractalType::Phoenix => {
-                self.center = [0.0, 0.0];
+                self.set_center([0.0, 0.0]);
                 self.zoom = 1.2;
                 self.julia_c = [0.5667, -0.5]; // Classic Phoenix parameters
             }
@@ -247,14 +453,16 @@ impl FractalParams {
     }
 }
 
-/// Location preset
-#[derive(Debug, Clone)]
+/// Location preset, as listed in `assets/presets.json` and user preset packs
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocationPreset {
-    pub name: &'static str,
-    pub center: [f32; 2],
-    pub zoom: f32,
+    pub name: String,
+    pub center: [Coordinate; 2],
+    pub zoom: f64,
     pub fractal_type: FractalType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub power: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub julia_c: Option<[f32; 2]>,
 }
 
@@ -0,0 +1,312 @@
+//! Offscreen rendering without a window or surface
+//! Uses a hardware adapter when one exists, then a software adapter, then the CPU renderer
+//! Images larger than one texture are rendered as tiles and stitched together
+
+use wgpu::{
+    Backends, BufferDescriptor, BufferUsages, Device, DeviceDescriptor, Extent3d, Features,
+    ImageCopyBuffer, ImageDataLayout, Instance, InstanceDescriptor, Limits, Queue,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+};
+
+use crate::constants::export;
+use crate::cpu;
+use crate::fractal::FractalParams;
+use crate::renderer::FractalRenderer;
+use crate::viewport::Viewport;
+
+/// Matches the sRGB swapchain format so offscreen output looks like the on-screen view
+const OFFSCREEN_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+/// GPU device plus fractal pipeline targeting an offscreen texture
+pub struct OffscreenRenderer {
+    device: Device,
+    queue: Queue,
+    renderer: FractalRenderer,
+    adapter_name: String,
+}
+
+impl OffscreenRenderer {
+    /// Create an offscreen renderer, preferring a hardware adapter over a software one
+    pub async fn new() -> Result<Self, String> {
+        let instance = Instance::new(InstanceDescriptor {
+            backends: Backends::all(),
+            ..Default::default()
+        });
+
+        let adapter = match Self::request_adapter(&instance, false).await {
+            Some(adapter) => adapter,
+            None => {
+                log::warn!("No hardware GPU adapter found, trying a software adapter");
+                Self::request_adapter(&instance, true)
+                    .await
+                    .ok_or_else(|| "Failed to find a GPU or software adapter".to_string())?
+            }
+        };
+
+        let adapter_info = adapter.get_info();
+        log::info!("Offscreen adapter: {:?}", adapter_info);
+
+        // Same baseline limits as the windowed path, but allow the adapter's full texture size
+        let (device, queue) = adapter
+            .request_device(
+                &DeviceDescriptor {
+                    label: Some("fractal-offscreen-device"),
+                    required_features: Features::empty(),
+                    required_limits: Limits::downlevel_webgl2_defaults()
+                        .using_resolution(adapter.limits()),
+                    memory_hints: Default::default(),
+                },
+                None,
+            )
+            .await
+            .map_err(|e| format!("Failed to create device: {e}"))?;
+
+        let mut renderer = FractalRenderer::new(&device, OFFSCREEN_FORMAT, 1, 1);
+        // Each render is read back at once, so its orbit cannot arrive a frame later
+        renderer.block_on_orbits();
+
+        Ok(Self {
+            device,
+            queue,
+            renderer,
+            adapter_name: adapter_info.name,
+        })
+    }
+
+    async fn request_adapter(instance: &Instance, force_fallback_adapter: bool) -> Option<wgpu::Adapter> {
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter,
+            })
+            .await
+    }
+
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    /// Largest width or height this device can render in one pass
+    pub fn max_dimension(&self) -> u32 {
+        self.device.limits().max_texture_dimension_2d
+    }
+
+    /// Render `params` into a tightly packed RGBA8 (sRGB) buffer of `width` x `height`
+    /// `params.resolution` is overridden with the output size
+    pub async fn render(&mut self, params: &FractalParams, width: u32, height: u32) -> Result<Vec<u8>, String> {
+        let max = self.max_dimension();
+        if width == 0 || height == 0 || width > max || height > max {
+            return Err(format!("Output size {width}x{height} is outside 1..={max}"));
+        }
+
+        let mut params = *params;
+        params.resolution = [width as f32, height as f32];
+
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("fractal-offscreen-texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: OFFSCREEN_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+
+        // Rows in a texture-to-buffer copy must be padded to COPY_BYTES_PER_ROW_ALIGNMENT
+        let tight_row = width * 4;
+        let padded_row = tight_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = self.device.create_buffer(&BufferDescriptor {
+            label: Some("fractal-offscreen-readback"),
+            size: padded_row as u64 * height as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.renderer.render(&self.device, &self.queue, &mut encoder, &view, &params, (width, height));
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer: &readback,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            size,
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = futures_channel::oneshot::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        // Native backends only make progress when polled; on the web the browser drives it
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .await
+            .map_err(|_| "Readback was cancelled".to_string())?
+            .map_err(|e| format!("Failed to map readback buffer: {e}"))?;
+
+        let mut pixels = Vec::with_capacity(tight_row as usize * height as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_row as usize) {
+                pixels.extend_from_slice(&row[..tight_row as usize]);
+            }
+        }
+        readback.unmap();
+
+        Ok(pixels)
+    }
+}
+
+/// Parameters for the `tile_width` x `tile_height` tile at pixel (`x0`, `y0`) of a
+/// `width` x `height` image of `params`
+/// Pixel size in the complex plane is kept identical, so stitched tiles line up exactly
+pub fn tile_params(
+    params: &FractalParams,
+    width: u32,
+    height: u32,
+    x0: u32,
+    y0: u32,
+    tile_width: u32,
+    tile_height: u32,
+) -> FractalParams {
+    let viewport = Viewport::for_screen(params, (width, height));
+    let center = viewport.screen_to_coordinate([
+        x0 as f64 + tile_width as f64 / 2.0,
+        y0 as f64 + tile_height as f64 / 2.0,
+    ]);
+
+    let mut tile = *params;
+    tile.center = center;
+    // Fewer rows per tile means a proportionally higher zoom for the same pixel size
+    tile.zoom = viewport.zoom * height as f64 / tile_height as f64;
+    tile.resolution = [tile_width as f32, tile_height as f32];
+    tile.ui_offset = 0.0;
+    tile.ui_offset_y = 0.0;
+    tile
+}
+
+/// Renders tiles on the GPU when an adapter is available and on the CPU otherwise
+pub struct TileRenderer {
+    gpu: Option<OffscreenRenderer>,
+}
+
+impl TileRenderer {
+    pub async fn new() -> Self {
+        let gpu = match OffscreenRenderer::new().await {
+            Ok(gpu) => Some(gpu),
+            Err(e) => {
+                log::warn!("{}, using CPU renderer", e);
+                None
+            }
+        };
+        Self { gpu }
+    }
+
+    /// A renderer that never touches the GPU and always takes the CPU path
+    pub fn cpu() -> Self {
+        Self { gpu: None }
+    }
+
+    /// Edge length of the square tiles an image is split into
+    pub fn tile_size(&self) -> u32 {
+        self.gpu
+            .as_ref()
+            .map_or(export::MAX_TILE_SIZE, |gpu| gpu.max_dimension().min(export::MAX_TILE_SIZE))
+    }
+
+    /// Render one tile, falling back to the CPU if the GPU path fails
+    pub async fn render(&mut self, params: &FractalParams, width: u32, height: u32) -> Vec<u8> {
+        if let Some(gpu) = self.gpu.as_mut() {
+            match gpu.render(params, width, height).await {
+                Ok(pixels) => return pixels,
+                Err(e) => log::warn!("Offscreen GPU render failed ({}), using CPU renderer", e),
+            }
+        }
+
+        let mut params = *params;
+        params.resolution = [width.max(1) as f32, height.max(1) as f32];
+        cpu::render(&params, width, height)
+    }
+
+    /// Render a `width` x `height` image of `params` of any size, tile by tile
+    /// `on_band` receives consecutive horizontal bands of tightly packed RGBA8 rows, top to bottom,
+    /// so callers can stream huge images without holding them in memory
+    pub async fn render_bands(
+        &mut self,
+        params: &FractalParams,
+        width: u32,
+        height: u32,
+        mut on_band: impl FnMut(&[u8]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let tile = self.tile_size();
+        let row_bytes = width as usize * 4;
+
+        for y0 in (0..height).step_by(tile as usize) {
+            let band_height = tile.min(height - y0);
+            let mut band = vec![0u8; row_bytes * band_height as usize];
+
+            for x0 in (0..width).step_by(tile as usize) {
+                let tile_width = tile.min(width - x0);
+                let tile_params = tile_params(params, width, height, x0, y0, tile_width, band_height);
+                let pixels = self.render(&tile_params, tile_width, band_height).await;
+
+                let tile_row_bytes = tile_width as usize * 4;
+                for (row, src) in pixels.chunks(tile_row_bytes).enumerate() {
+                    let start = row * row_bytes + x0 as usize * 4;
+                    band[start..start + tile_row_bytes].copy_from_slice(src);
+                }
+            }
+
+            on_band(&band)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Render `params` to an RGBA8 (sRGB) buffer without a window
+/// Sizes beyond the texture limit are rendered in tiles; the CPU renderer is used
+/// when no adapter is available or the GPU path fails
+pub async fn render_rgba(params: &FractalParams, width: u32, height: u32) -> Vec<u8> {
+    let width = width.max(1);
+    let height = height.max(1);
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+
+    let mut renderer = TileRenderer::new().await;
+    let rendered = renderer
+        .render_bands(params, width, height, |band| {
+            pixels.extend_from_slice(band);
+            Ok(())
+        })
+        .await;
+
+    match rendered {
+        Ok(()) => pixels,
+        Err(e) => {
+            log::warn!("Tiled render failed ({}), using CPU renderer", e);
+            let mut params = *params;
+            params.resolution = [width as f32, height as f32];
+            cpu::render(&params, width, height)
+        }
+    }
+}
+
+/// Blocking wrapper around [`render_rgba`] for batch jobs and tools
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_rgba_blocking(params: &FractalParams, width: u32, height: u32) -> Vec<u8> {
+    pollster::block_on(render_rgba(params, width, height))
+}
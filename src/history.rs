@@ -0,0 +1,137 @@
+//! Undo/redo of view and parameter edits
+//! The app reports the parameters every frame; a change becomes one history entry once
+//! it has settled, so a whole drag, slider scrub or animation undoes in one step
+
+use std::collections::VecDeque;
+
+use crate::constants::history;
+use crate::fractal::FractalParams;
+
+/// The parameters that make up a history entry; per-frame layout fields are cleared
+fn snapshot(params: &FractalParams) -> FractalParams {
+    FractalParams {
+        resolution: [0.0, 0.0],
+        ui_offset: 0.0,
+        ui_offset_y: 0.0,
+        ..*params
+    }
+}
+
+/// Bounded undo/redo stacks of parameter snapshots
+pub struct History {
+    undo: VecDeque<FractalParams>,
+    redo: Vec<FractalParams>,
+    /// The state recorded by the last entry; `None` before the first observation
+    settled: Option<FractalParams>,
+    /// The latest state seen and when it last changed
+    latest: FractalParams,
+    changed_at: f64,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            settled: None,
+            latest: FractalParams::default(),
+            changed_at: 0.0,
+        }
+    }
+
+    /// Report the parameters at time `now`
+    /// While `busy` (a drag is held, an animation runs, ...) changes keep coalescing;
+    /// afterwards they become one entry once they have been still for `SETTLE_TIME`.
+    /// Returns true when a new entry was recorded
+    pub fn observe(&mut self, params: &FractalParams, busy: bool, now: f64) -> bool {
+        let current = snapshot(params);
+        let Some(settled) = self.settled else {
+            self.settled = Some(current);
+            self.latest = current;
+            self.changed_at = now;
+            return false;
+        };
+
+        if current != self.latest {
+            self.latest = current;
+            self.changed_at = now;
+        }
+        if !busy && now - self.changed_at >= history::SETTLE_TIME && self.latest != settled {
+            self.record(self.latest);
+            return true;
+        }
+        false
+    }
+
+    /// Whether there is anything to undo, including a change that has not settled yet
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || self.settled.is_some_and(|settled| settled != self.latest)
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Step back to the previous entry; returns false if there is none
+    pub fn undo(&mut self, params: &mut FractalParams) -> bool {
+        self.flush(params);
+        let Some(previous) = self.undo.pop_back() else {
+            return false;
+        };
+        if let Some(settled) = self.settled.replace(previous) {
+            self.redo.push(settled);
+        }
+        self.restore(previous, params);
+        true
+    }
+
+    /// Step forward again after an undo; returns false if there is nothing to redo
+    pub fn redo(&mut self, params: &mut FractalParams) -> bool {
+        self.flush(params);
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        if let Some(settled) = self.settled.replace(next) {
+            self.undo.push_back(settled);
+        }
+        self.restore(next, params);
+        true
+    }
+
+    /// Turn a change that has not settled yet into an entry right away
+    fn flush(&mut self, params: &FractalParams) {
+        let current = snapshot(params);
+        if self.settled.is_some_and(|settled| settled != current) {
+            self.record(current);
+        }
+    }
+
+    /// Make `state` the newest entry; a new edit discards the redo stack
+    fn record(&mut self, state: FractalParams) {
+        if let Some(settled) = self.settled.replace(state) {
+            self.undo.push_back(settled);
+            if self.undo.len() > history::MAX_ENTRIES {
+                self.undo.pop_front();
+            }
+        }
+        self.redo.clear();
+        self.latest = state;
+    }
+
+    /// Write `state` into `params`, keeping the current layout fields
+    fn restore(&mut self, state: FractalParams, params: &mut FractalParams) {
+        self.latest = state;
+        *params = FractalParams {
+            resolution: params.resolution,
+            ui_offset: params.ui_offset,
+            ui_offset_y: params.ui_offset_y,
+            ..state
+        };
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,41 +1,251 @@
-use winit::event::MouseButton;
+use winit::event::{MouseButton, TouchPhase};
+use winit::keyboard::{Key, ModifiersState};
 
-/// Input state for handling mouse interactions
+use crate::constants::input::MIN_BOX_SIZE;
+use crate::keymap::{Action, Keymap};
+use crate::viewport::{wrap_angle, Viewport};
+
+/// A rectangle dragged out with Shift held, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxSelection {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    /// Dragged with the right button: zoom out so the current view fits in the box
+    pub zoom_out: bool,
+}
+
+impl BoxSelection {
+    /// Corners as (min, max)
+    pub fn bounds(&self) -> ((f32, f32), (f32, f32)) {
+        (
+            (self.start.0.min(self.end.0), self.start.1.min(self.end.1)),
+            (self.start.0.max(self.end.0), self.start.1.max(self.end.1)),
+        )
+    }
+
+    fn button(&self) -> MouseButton {
+        if self.zoom_out {
+            MouseButton::Right
+        } else {
+            MouseButton::Left
+        }
+    }
+}
+
+/// What a pointer move with a button held does to the view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragMove {
+    /// Left drag: move the content by this many pixels
+    Pan(f32, f32),
+    /// Right drag: turn the view as the cursor moves from `from` to `to`
+    Rotate { from: (f32, f32), to: (f32, f32) },
+}
+
+/// View change from a touch move: pan by `pan` pixels, then zoom by `zoom` and turn by
+/// `rotation` radians (as in [`Viewport::rotate_by`]) about `center`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchGesture {
+    pub pan: (f32, f32),
+    pub zoom: f32,
+    pub rotation: f32,
+    pub center: (f32, f32),
+}
+
+impl TouchGesture {
+    /// Move `viewport` so the content under the fingers follows them
+    pub fn apply(&self, viewport: &mut Viewport) {
+        let center = [self.center.0 as f64, self.center.1 as f64];
+        viewport.pan(self.pan.0 as f64, self.pan.1 as f64);
+        viewport.zoom_at(self.zoom as f64, center);
+        viewport.rotate_at(self.rotation as f64, center);
+    }
+}
+
+/// Input state for handling mouse, touch and keyboard interactions
 pub struct InputState {
     dragging: bool,
+    rotating: bool,
     last_pos: Option<(f32, f32)>,
+    cursor: Option<(f32, f32)>,
+    modifiers: ModifiersState,
+    selection: Option<BoxSelection>,
+    keymap: Keymap,
+    /// Active touch points by finger id, in the order they went down
+    touches: Vec<(u64, (f32, f32))>,
 }
 
 impl InputState {
     pub fn new() -> Self {
         Self {
             dragging: false,
+            rotating: false,
             last_pos: None,
+            cursor: None,
+            modifiers: ModifiersState::empty(),
+            selection: None,
+            keymap: Keymap::load(),
+            touches: Vec::new(),
+        }
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Handle a key press, returning the bound action
+    pub fn handle_key(&mut self, key: &Key, pressed: bool, repeat: bool) -> Option<Action> {
+        if !pressed {
+            return None;
         }
+        self.keymap
+            .action_for(key, self.modifiers)
+            .filter(|action| !repeat || action.repeats())
+    }
+
+    /// Whether a drag, box selection or touch gesture is in progress
+    pub fn is_interacting(&self) -> bool {
+        self.dragging || self.rotating || self.selection.is_some() || !self.touches.is_empty()
+    }
+
+    pub fn handle_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
     }
 
     /// Handle mouse button press/release
-    pub fn handle_mouse_button(&mut self, button: MouseButton, pressed: bool) {
-        if button == MouseButton::Left {
-            self.dragging = pressed;
-            if !pressed {
+    /// Left drags pan and right drags rotate;
+    /// Shift+left or Shift+right starts a box selection instead of a drag;
+    /// the finished box is returned on release if it is large enough to zoom to
+    pub fn handle_mouse_button(&mut self, button: MouseButton, pressed: bool) -> Option<BoxSelection> {
+        if let Some(selection) = self.selection {
+            if !pressed && button == selection.button() {
+                self.selection = None;
+                let ((x0, y0), (x1, y1)) = selection.bounds();
+                return (x1 - x0 >= MIN_BOX_SIZE && y1 - y0 >= MIN_BOX_SIZE).then_some(selection);
+            }
+            return None;
+        }
+
+        let box_button = matches!(button, MouseButton::Left | MouseButton::Right);
+        if pressed && box_button && self.modifiers.shift_key() {
+            if let Some(cursor) = self.cursor {
+                self.dragging = false;
+                self.rotating = false;
                 self.last_pos = None;
+                self.selection = Some(BoxSelection {
+                    start: cursor,
+                    end: cursor,
+                    zoom_out: button == MouseButton::Right,
+                });
+                return None;
             }
         }
+
+        match button {
+            MouseButton::Left => self.dragging = pressed,
+            MouseButton::Right => self.rotating = pressed,
+            _ => return None,
+        }
+        if !self.dragging && !self.rotating {
+            self.last_pos = None;
+        }
+        None
+    }
+
+    /// The box currently being dragged out, if any
+    pub fn selection(&self) -> Option<BoxSelection> {
+        self.selection
     }
 
-    /// Handle cursor movement, returns delta if dragging
-    pub fn handle_cursor_move(&mut self, x: f32, y: f32) -> Option<(f32, f32)> {
+    /// Handle cursor movement, returns the drag step if a button is held
+    /// Panning wins when both buttons are down
+    pub fn handle_cursor_move(&mut self, x: f32, y: f32) -> Option<DragMove> {
+        self.cursor = Some((x, y));
+        if let Some(selection) = self.selection.as_mut() {
+            selection.end = (x, y);
+            return None;
+        }
+        if !self.dragging && !self.rotating {
+            return None;
+        }
+
+        let last = self.last_pos.replace((x, y))?;
         if self.dragging {
-            if let Some((last_x, last_y)) = self.last_pos {
-                let delta = (x - last_x, y - last_y);
-                self.last_pos = Some((x, y));
-                return Some(delta);
-            } else {
-                self.last_pos = Some((x, y));
+            Some(DragMove::Pan(x - last.0, y - last.1))
+        } else {
+            Some(DragMove::Rotate { from: last, to: (x, y) })
+        }
+    }
+
+    /// Handle a touch event; the first two fingers drive the gesture
+    /// One finger pans, two fingers pan with their midpoint and pinch-zoom around it
+    pub fn handle_touch(&mut self, id: u64, phase: TouchPhase, x: f32, y: f32) -> Option<TouchGesture> {
+        match phase {
+            TouchPhase::Started => {
+                self.touches.push((id, (x, y)));
+                None
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.retain(|(touch, _)| *touch != id);
+                None
+            }
+            TouchPhase::Moved => {
+                let index = self.touches.iter().position(|(touch, _)| *touch == id)?;
+                let before = self.touches[index].1;
+                self.touches[index].1 = (x, y);
+
+                match self.touches.as_slice() {
+                    [_] => Some(TouchGesture {
+                        pan: (x - before.0, y - before.1),
+                        zoom: 1.0,
+                        rotation: 0.0,
+                        center: (x, y),
+                    }),
+                    [first, second, ..] if index < 2 => {
+                        let other = if index == 0 { second.1 } else { first.1 };
+                        let old_mid = ((before.0 + other.0) / 2.0, (before.1 + other.1) / 2.0);
+                        let new_mid = ((x + other.0) / 2.0, (y + other.1) / 2.0);
+                        let old_dist = (before.0 - other.0).hypot(before.1 - other.1);
+                        let new_dist = (x - other.0).hypot(y - other.1);
+                        // Screen y points down, so the finger pair turning clockwise on screen
+                        // grows this angle and the image has to turn the same way (negative)
+                        let old_angle = (before.1 - other.1).atan2(before.0 - other.0);
+                        let new_angle = (y - other.1).atan2(x - other.0);
+                        let pinched = old_dist > 1.0;
+                        Some(TouchGesture {
+                            pan: (new_mid.0 - old_mid.0, new_mid.1 - old_mid.1),
+                            zoom: if pinched { new_dist / old_dist } else { 1.0 },
+                            rotation: if pinched { wrap_angle((old_angle - new_angle) as f64) as f32 } else { 0.0 },
+                            center: new_mid,
+                        })
+                    }
+                    _ => None,
+                }
             }
         }
-        None
+    }
+
+    /// Number of fingers currently down
+    pub fn touch_count(&self) -> usize {
+        self.touches.len()
+    }
+
+    /// Forget the cursor position when it leaves the window
+    pub fn handle_cursor_left(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Last known cursor position in physical pixels, if the cursor is over the window
+    pub fn cursor_position(&self) -> Option<(f32, f32)> {
+        self.cursor
+    }
+
+    /// Screen point a wheel or trackpad zoom keeps fixed: the cursor, or the visible center
+    /// of `viewport` when the cursor is outside the window
+    pub fn zoom_anchor(&self, viewport: &Viewport) -> [f64; 2] {
+        match self.cursor {
+            Some((x, y)) => [x as f64, y as f64],
+            None => viewport.offset_to_screen([0.0, 0.0]),
+        }
     }
 }
 
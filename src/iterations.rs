@@ -0,0 +1,255 @@
+//! Automatic iteration count
+//! The zoom sets how far a coarse grid of samples over the view is iterated; the count is then
+//! chosen so only a small share of the escaping samples along the set's boundary is cut off.
+//! The grid is far coarser than the screen and misses the thinnest filaments; the bias makes up for it
+//! While the app runs, the grid is sampled a few rows per frame so deep views do not stall it;
+//! deep views wait for the renderer's reference orbit, and small pans and zooms keep the samples
+
+use std::sync::Arc;
+
+use crate::constants::ui;
+use crate::cpu;
+use crate::fractal::FractalParams;
+use crate::perturbation::{PerturbationParams, ReferenceOrbit, ReferenceOrbits};
+
+/// Iterations chosen at zoom 1 when the samples say nothing
+pub const BASE_ITERATIONS: f32 = 100.0;
+/// The zoom estimate grows as (1 + decades of zoom) to this power
+pub const ZOOM_EXPONENT: f32 = 1.5;
+/// Samples iterate up to this multiple of the zoom estimate
+pub const SAMPLE_HEADROOM: f32 = 4.0;
+/// Sample grid columns and rows spread over the view
+pub const SAMPLE_GRID: [u32; 2] = [40, 25];
+/// Largest share of the escaping boundary samples the chosen count may cut off
+pub const CLIPPED_SHARE: f32 = 0.02;
+/// Sample grid rows [`AutoIterations::update`] iterates per frame
+pub const SAMPLE_ROWS_PER_FRAME: u32 = 2;
+/// Share of the view height the view may pan before [`AutoIterations`] samples it again
+pub const RESAMPLE_SHIFT: f64 = 0.1;
+/// Factor the zoom may change by before [`AutoIterations`] samples the view again
+pub const RESAMPLE_ZOOM: f64 = 1.25;
+
+/// Rough iteration count for `zoom`, used when the samples say nothing (e.g. all inside the set)
+pub fn zoom_iterations(zoom: f64) -> u32 {
+    let decades = zoom.log10().max(0.0) as f32;
+    let estimate = BASE_ITERATIONS * (1.0 + decades).powf(ZOOM_EXPONENT);
+    estimate.round().clamp(ui::MIN_ITERATIONS, ui::MAX_DEEP_ITERATIONS) as u32
+}
+
+/// Iterations the samples are run to at `zoom`
+pub fn sample_limit(zoom: f64) -> u32 {
+    (zoom_iterations(zoom) as f32 * SAMPLE_HEADROOM).min(ui::MAX_DEEP_ITERATIONS) as u32
+}
+
+/// Escape counts of a `columns` x `rows` grid over a view, gathered a few rows at a time
+pub struct EscapeSampler {
+    params: FractalParams,
+    roots: cpu::NewtonRoots,
+    reference: Option<(Arc<ReferenceOrbit>, PerturbationParams)>,
+    columns: u32,
+    rows: u32,
+    counts: Vec<u32>,
+}
+
+impl EscapeSampler {
+    /// Sample the view of `params` up to `limit` iterations; the reference orbit of perturbation
+    /// views is computed here, the samples themselves by [`Self::sample_rows`]
+    pub fn new(params: &FractalParams, columns: u32, rows: u32, limit: u32) -> Self {
+        let params = FractalParams { max_iter: limit, ..*params };
+        let orbit = params.perturbation().then(|| Arc::new(ReferenceOrbit::new(&params)));
+        Self::with_orbit(&params, columns, rows, orbit)
+    }
+
+    /// Sample the view of `params` up to `params.max_iter` against an `orbit` computed elsewhere,
+    /// e.g. by [`ReferenceOrbits`]; it must cover `params.max_iter` for perturbation views
+    pub fn with_orbit(params: &FractalParams, columns: u32, rows: u32, orbit: Option<Arc<ReferenceOrbit>>) -> Self {
+        let params = *params;
+        let reference = orbit.filter(|_| params.perturbation()).map(|orbit| {
+            let uniform = orbit.uniform(&params);
+            (orbit, uniform)
+        });
+        Self {
+            roots: cpu::NewtonRoots::new(&params),
+            params,
+            reference,
+            columns,
+            rows,
+            counts: Vec::with_capacity((columns * rows) as usize),
+        }
+    }
+
+    /// Iterate up to `rows` more rows of the grid. Returns true once every row is done
+    pub fn sample_rows(&mut self, rows: u32) -> bool {
+        let reference = self.reference.as_ref().map(|(orbit, uniform)| (&**orbit, uniform));
+        let start = self.counts.len() as u32 / self.columns.max(1);
+        for y in start..(start + rows).min(self.rows) {
+            for x in 0..self.columns {
+                let (result, _) =
+                    cpu::iterate_pixel(&self.params, &self.roots, reference, x, y, self.columns, self.rows);
+                self.counts.push((result.x as u32).min(self.params.max_iter));
+            }
+        }
+        self.is_done()
+    }
+
+    /// True once every row of the grid is sampled
+    pub fn is_done(&self) -> bool {
+        self.counts.len() as u32 >= self.columns * self.rows
+    }
+
+    /// Counts of the rows sampled so far, laid out as by [`escape_counts`]
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+}
+
+/// Escape counts of a `columns` x `rows` grid spread over the view of `params`, row by row,
+/// iterated up to `limit`; samples that never escape count `limit`
+pub fn escape_counts(params: &FractalParams, columns: u32, rows: u32, limit: u32) -> Vec<u32> {
+    let mut sampler = EscapeSampler::new(params, columns, rows, limit);
+    sampler.sample_rows(rows);
+    sampler.counts
+}
+
+/// The count that lets all but [`CLIPPED_SHARE`] of the boundary samples in `counts`
+/// (laid out as by [`escape_counts`]) escape. Boundary samples escape within `limit` but have a
+/// neighbour that does not; without any, every escaping sample counts. `None` if nothing escapes
+pub fn choose(counts: &[u32], columns: usize, limit: u32) -> Option<u32> {
+    let rows = counts.len() / columns.max(1);
+    let inside = |x: usize, y: usize| x < columns && y < rows && counts[y * columns + x] >= limit;
+
+    let mut escaped = Vec::new();
+    let mut boundary = Vec::new();
+    for y in 0..rows {
+        for x in 0..columns {
+            let count = counts[y * columns + x];
+            if count >= limit {
+                continue;
+            }
+            escaped.push(count);
+            // Out-of-range neighbours wrap to usize::MAX and are never inside
+            let neighbours = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            if neighbours.iter().any(|&(nx, ny)| inside(nx, ny)) {
+                boundary.push(count);
+            }
+        }
+    }
+
+    let mut samples = if boundary.is_empty() { escaped } else { boundary };
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_unstable();
+    let kept = (samples.len() as f32 * (1.0 - CLIPPED_SHARE)).ceil() as usize;
+    Some(samples[kept.clamp(1, samples.len()) - 1] + 1)
+}
+
+/// `params` with `max_iter` at the limit the [`SAMPLE_GRID`] is iterated to
+fn grid_params(params: &FractalParams) -> FractalParams {
+    let limit = sample_limit(params.zoom).min(params.max_iterations());
+    FractalParams { max_iter: limit, ..*params }
+}
+
+/// A sampler over [`SAMPLE_GRID`] for the automatic count of `params`, sharing the reference
+/// orbit with the renderer through `orbits`; `None` while a perturbation view waits for it
+fn grid_sampler(params: &FractalParams, orbits: &mut ReferenceOrbits) -> Option<EscapeSampler> {
+    let grid = grid_params(params);
+    let orbit = if grid.perturbation() { Some(orbits.request(&grid)?) } else { None };
+    let [columns, rows] = SAMPLE_GRID;
+    Some(EscapeSampler::with_orbit(&grid, columns, rows, orbit))
+}
+
+/// The count chosen from the finished `sampler` made by [`grid_sampler`]
+fn grid_choice(sampler: &EscapeSampler) -> u32 {
+    let params = &sampler.params;
+    choose(sampler.counts(), sampler.columns as usize, params.max_iter)
+        .unwrap_or_else(|| zoom_iterations(params.zoom).min(params.max_iterations()))
+}
+
+/// The automatic count for the view of `params` before the bias is applied
+pub fn sampled_iterations(params: &FractalParams) -> u32 {
+    let grid = grid_params(params);
+    let [columns, rows] = SAMPLE_GRID;
+    let mut sampler = EscapeSampler::new(&grid, columns, rows, grid.max_iter);
+    sampler.sample_rows(rows);
+    grid_choice(&sampler)
+}
+
+/// `count` scaled by `bias` and clamped to the slider range, which ends at `max`
+pub fn biased(count: u32, bias: f32, max: u32) -> u32 {
+    (count as f32 * bias).round().clamp(ui::MIN_ITERATIONS, max as f32) as u32
+}
+
+/// The automatic iteration count for `params`, scaled by `params.iteration_bias`
+pub fn auto_iterations(params: &FractalParams) -> u32 {
+    biased(sampled_iterations(params), params.iteration_bias, params.max_iterations())
+}
+
+/// Keeps `max_iter` at the automatic count while [`FractalParams::auto_iterations`] is on
+/// The view is only sampled once it holds still, and again only after it moves past
+/// [`RESAMPLE_SHIFT`] or [`RESAMPLE_ZOOM`]; [`SAMPLE_ROWS_PER_FRAME`] rows are sampled per update
+/// and `max_iter` is kept until all are done
+#[derive(Default)]
+pub struct AutoIterations {
+    /// View the last count was sampled for, with the fields that cannot change it cleared
+    view: Option<FractalParams>,
+    sampled: u32,
+    /// View being sampled, keyed like `view`, with its partly filled grid
+    pending: Option<(FractalParams, EscapeSampler)>,
+}
+
+/// True if the views `a` and `b` differ only by a pan within [`RESAMPLE_SHIFT`] of the view
+/// height and a zoom within [`RESAMPLE_ZOOM`], so one's count still suits the other
+fn close(a: &FractalParams, b: &FractalParams) -> bool {
+    if (FractalParams { center: b.center, zoom: b.zoom, ..*a }) != *b {
+        return false;
+    }
+    let shift = [0, 1].map(|i| (a.center[i] - b.center[i]).to_f64());
+    // The view is 2 / zoom tall
+    let panned = shift[0].hypot(shift[1]) * a.zoom.min(b.zoom) / 2.0;
+    panned <= RESAMPLE_SHIFT && (a.zoom / b.zoom).ln().abs() <= RESAMPLE_ZOOM.ln()
+}
+
+impl AutoIterations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `params.max_iter` to the automatic count unless the mode is off or the view is `busy`
+    /// Deep views are sampled against the reference orbit from `orbits`, shared with the renderer
+    /// Returns true if `params` changed
+    pub fn update(&mut self, params: &mut FractalParams, busy: bool, orbits: &mut ReferenceOrbits) -> bool {
+        if !params.auto_iterations() || busy {
+            return false;
+        }
+
+        let view = FractalParams {
+            max_iter: 0,
+            color_scheme: 0,
+            flags: 0,
+            iteration_bias: 1.0,
+            ..*params
+        };
+        if !self.view.is_some_and(|sampled| close(&sampled, &view)) {
+            let pending = match self.pending.take() {
+                Some(pending) if close(&pending.0, &view) => pending,
+                _ => match grid_sampler(params, orbits) {
+                    Some(sampler) => (view, sampler),
+                    None => return false,
+                },
+            };
+            let (key, sampler) = self.pending.insert(pending);
+            if !sampler.sample_rows(SAMPLE_ROWS_PER_FRAME) {
+                return false;
+            }
+            self.sampled = grid_choice(sampler);
+            self.view = Some(*key);
+            self.pending = None;
+        }
+
+        let max_iter = biased(self.sampled, params.iteration_bias, params.max_iterations());
+        let changed = params.max_iter != max_iter;
+        params.max_iter = max_iter;
+        changed
+    }
+}
@@ -0,0 +1,245 @@
+//! Keyboard shortcuts: one rebindable table from key names to actions
+//! Keys are named after winit's logical keys, so bindings follow the user's layout;
+//! chords with Ctrl are written like "Ctrl+Z" or "Ctrl+Shift+Z"
+
+use serde::{Deserialize, Serialize};
+use winit::keyboard::{Key, ModifiersState};
+
+use crate::files;
+
+/// Current keymap document version
+pub const KEYMAP_VERSION: u32 = 1;
+
+/// Name of the stored keymap document
+const STORE_NAME: &str = "keymap";
+
+/// On-disk layout, tagged with the format version it was written with
+#[derive(Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    bindings: Vec<(String, Action)>,
+}
+
+/// Something a key can do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    PanLeft,
+    PanRight,
+    PanUp,
+    PanDown,
+    ZoomIn,
+    ZoomOut,
+    RotateLeft,
+    RotateRight,
+    Reset,
+    NextFractal,
+    NextColorScheme,
+    MoreIterations,
+    FewerIterations,
+    ToggleHelp,
+    Undo,
+    Redo,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::PanLeft,
+            Action::PanRight,
+            Action::PanUp,
+            Action::PanDown,
+            Action::ZoomIn,
+            Action::ZoomOut,
+            Action::RotateLeft,
+            Action::RotateRight,
+            Action::Reset,
+            Action::NextFractal,
+            Action::NextColorScheme,
+            Action::MoreIterations,
+            Action::FewerIterations,
+            Action::ToggleHelp,
+            Action::Undo,
+            Action::Redo,
+        ]
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::PanLeft => "Pan left",
+            Action::PanRight => "Pan right",
+            Action::PanUp => "Pan up",
+            Action::PanDown => "Pan down",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::RotateLeft => "Rotate counter-clockwise",
+            Action::RotateRight => "Rotate clockwise",
+            Action::Reset => "Reset view",
+            Action::NextFractal => "Next fractal type",
+            Action::NextColorScheme => "Next color scheme",
+            Action::MoreIterations => "More iterations",
+            Action::FewerIterations => "Fewer iterations",
+            Action::ToggleHelp => "Show/hide this help",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+        }
+    }
+
+    /// Whether holding the key down repeats the action
+    pub fn repeats(&self) -> bool {
+        !matches!(
+            self,
+            Action::Reset | Action::NextFractal | Action::NextColorScheme | Action::ToggleHelp
+        )
+    }
+}
+
+/// Key name to action bindings; a key maps to at most one action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: Vec<(String, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            ("ArrowLeft", Action::PanLeft),
+            ("A", Action::PanLeft),
+            ("ArrowRight", Action::PanRight),
+            ("D", Action::PanRight),
+            ("ArrowUp", Action::PanUp),
+            ("W", Action::PanUp),
+            ("ArrowDown", Action::PanDown),
+            ("S", Action::PanDown),
+            ("+", Action::ZoomIn),
+            ("=", Action::ZoomIn),
+            ("-", Action::ZoomOut),
+            ("Q", Action::RotateLeft),
+            ("E", Action::RotateRight),
+            ("R", Action::Reset),
+            ("Tab", Action::NextFractal),
+            ("C", Action::NextColorScheme),
+            ("]", Action::MoreIterations),
+            ("[", Action::FewerIterations),
+            ("H", Action::ToggleHelp),
+            ("F1", Action::ToggleHelp),
+            ("Ctrl+Z", Action::Undo),
+            ("Ctrl+Y", Action::Redo),
+            ("Ctrl+Shift+Z", Action::Redo),
+        ];
+
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(key, action)| (key.to_string(), action))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Name of a key press as used in bindings: named keys by their winit name,
+    /// characters in upper case, with a "Ctrl+" (and then "Shift+") prefix when Ctrl is held
+    /// Keys held with Alt or Super have no name and are left to other shortcuts
+    pub fn key_name(key: &Key, modifiers: ModifiersState) -> Option<String> {
+        if modifiers.alt_key() || modifiers.super_key() {
+            return None;
+        }
+        let name = match key {
+            Key::Named(named) => format!("{named:?}"),
+            Key::Character(text) => text.to_uppercase(),
+            _ => return None,
+        };
+        Some(match (modifiers.control_key(), modifiers.shift_key()) {
+            (true, true) => format!("Ctrl+Shift+{name}"),
+            (true, false) => format!("Ctrl+{name}"),
+            (false, _) => name,
+        })
+    }
+
+    /// Bind `key` to `action`, replacing whatever `key` did before
+    pub fn bind(&mut self, key: &str, action: Action) {
+        let key = normalize(key);
+        self.bindings.retain(|(bound, _)| *bound != key);
+        self.bindings.push((key, action));
+    }
+
+    /// Remove every binding for `action`
+    pub fn unbind(&mut self, action: Action) {
+        self.bindings.retain(|(_, bound)| *bound != action);
+    }
+
+    pub fn action_for(&self, key: &Key, modifiers: ModifiersState) -> Option<Action> {
+        let name = Self::key_name(key, modifiers)?;
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == name)
+            .map(|(_, action)| *action)
+    }
+
+    /// Keys bound to `action`, in binding order
+    pub fn keys_for(&self, action: Action) -> impl Iterator<Item = &str> {
+        self.bindings
+            .iter()
+            .filter(move |(_, bound)| *bound == action)
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Serialize the bindings as a keymap document
+    pub fn to_json(&self) -> Result<String, String> {
+        let document = Document {
+            version: KEYMAP_VERSION,
+            bindings: self.bindings.clone(),
+        };
+        serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to write keymap: {e}"))
+    }
+
+    /// Parse a keymap document
+    /// Bindings go through [`Keymap::bind`], so a key bound twice keeps its last action
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document: Document = serde_json::from_str(text).map_err(|e| format!("Invalid keymap: {e}"))?;
+        if document.version == 0 || document.version > KEYMAP_VERSION {
+            return Err(format!("Unsupported keymap version {}", document.version));
+        }
+
+        let mut keymap = Self { bindings: Vec::new() };
+        for (key, action) in &document.bindings {
+            keymap.bind(key, *action);
+        }
+        Ok(keymap)
+    }
+
+    /// Read the keymap stored in the user's storage, falling back to the defaults
+    pub fn load() -> Self {
+        let Some(text) = files::read_config(STORE_NAME) else {
+            return Self::default();
+        };
+        Self::from_json(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring stored keymap: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Write the keymap back to the user's storage
+    pub fn store(&self) -> Result<(), String> {
+        files::write_config(STORE_NAME, &self.to_json()?)
+    }
+}
+
+/// Single characters and modifier prefixes are matched case-insensitively;
+/// named keys keep their spelling
+fn normalize(key: &str) -> String {
+    for prefix in ["Ctrl+Shift+", "Ctrl+"] {
+        let rest = key
+            .get(..prefix.len())
+            .filter(|head| head.eq_ignore_ascii_case(prefix))
+            .map(|_| &key[prefix.len()..]);
+        if let Some(rest) = rest.filter(|rest| !rest.is_empty()) {
+            return format!("{prefix}{}", normalize(rest));
+        }
+    }
+    if key.chars().count() == 1 {
+        key.to_uppercase()
+    } else {
+        key.to_string()
+    }
+}
@@ -3,25 +3,49 @@ use wasm_bindgen::prelude::*;
 use winit::{
     application::ApplicationHandler,
     dpi::PhysicalSize,
-    event::{ElementState, MouseScrollDelta, WindowEvent},
+    event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent},
     event_loop::{ActiveEventLoop, EventLoop},
     window::{Window, WindowId},
 };
 #[cfg(target_arch = "wasm32")]
 use winit::dpi::LogicalSize;
 
+pub mod animation;
+pub mod bookmarks;
 mod color;
 mod constants;
+pub mod cpu;
+pub mod deeplink;
+pub mod export;
+mod files;
+pub mod fixed;
 mod fractal;
-mod input;
+pub mod headless;
+pub mod history;
+pub mod input;
+pub mod iterations;
+pub mod keymap;
+pub mod perturbation;
+pub mod presets;
 mod renderer;
+pub mod scene;
 mod ui;
+pub mod viewport;
+pub mod views;
 mod webgpu;
 
-use crate::fractal::FractalParams;
-use crate::input::InputState;
+pub use crate::color::ColorScheme;
+pub use crate::fractal::{Coordinate, FractalParams, FractalType};
+pub use crate::viewport::Viewport;
+use crate::animation::Animator;
+use crate::bookmarks::Bookmarks;
+use crate::input::{DragMove, InputState};
+use crate::iterations::AutoIterations;
+use crate::keymap::Action;
+use crate::files::FileEvent;
+use crate::scene::Scene;
 use crate::renderer::FractalRenderer;
-use crate::ui::UiState;
+use crate::ui::{UiAction, UiState};
 use crate::webgpu::WebGpuState;
 
 /// Handle input events that affect fractal parameters
@@ -29,35 +53,307 @@ use crate::webgpu::WebGpuState;
 fn handle_input_event(
     event: &WindowEvent,
     input: &mut InputState,
+    ui: &mut UiState,
+    animator: &mut Animator,
     params: &mut FractalParams,
     gpu_size: (u32, u32),
 ) -> bool {
     match event {
-        WindowEvent::MouseInput { state: btn_state, button, .. } => {
-            input.handle_mouse_button(*button, *btn_state == ElementState::Pressed);
+        WindowEvent::KeyboardInput { event: key_event, .. } => {
+            let pressed = key_event.state == ElementState::Pressed;
+            match input.handle_key(&key_event.logical_key, pressed, key_event.repeat) {
+                Some(action) => apply_key_action(action, ui, params, gpu_size),
+                None => false,
+            }
+        }
+        WindowEvent::ModifiersChanged(modifiers) => {
+            input.handle_modifiers(modifiers.state());
             false
         }
+        WindowEvent::MouseInput { state: btn_state, button, .. } => {
+            let pressed = *btn_state == ElementState::Pressed;
+            if *button == MouseButton::Left {
+                if pressed {
+                    animator.grab();
+                } else {
+                    animator.release(animator.now());
+                }
+            }
+            match input.handle_mouse_button(*button, pressed) {
+                Some(selection) => {
+                    let ((x0, y0), (x1, y1)) = selection.bounds();
+                    let mut viewport = Viewport::for_screen(params, gpu_size);
+                    viewport.zoom_to_rect(
+                        [x0 as f64, y0 as f64],
+                        [x1 as f64, y1 as f64],
+                        selection.zoom_out,
+                    );
+                    viewport.apply(params);
+                    true
+                }
+                None => false,
+            }
+        }
         WindowEvent::CursorMoved { position, .. } => {
-            if let Some(delta) = input.handle_cursor_move(position.x as f32, position.y as f32) {
-                let (width, height) = gpu_size;
-                params.pan(delta.0, delta.1, width as f32, height as f32);
-                true
-            } else {
-                false
+            let Some(drag) = input.handle_cursor_move(position.x as f32, position.y as f32) else {
+                return false;
+            };
+            let mut viewport = Viewport::for_screen(params, gpu_size);
+            match drag {
+                DragMove::Pan(dx, dy) => {
+                    let delta = [dx as f64, dy as f64];
+                    viewport.pan(delta[0], delta[1]);
+                    animator.track_drag(delta, animator.now());
+                }
+                DragMove::Rotate { from, to } => {
+                    // Turn by the angle the cursor swept around the visible center
+                    let pivot = viewport.offset_to_screen([0.0, 0.0]);
+                    let angle = |(x, y): (f32, f32)| (y as f64 - pivot[1]).atan2(x as f64 - pivot[0]);
+                    // Screen y points down, so a visually clockwise sweep has a positive angle
+                    viewport.rotate_by(angle(from) - angle(to));
+                }
             }
+            viewport.apply(params);
+            true
         }
         WindowEvent::MouseWheel { delta, .. } => {
             let scroll = match delta {
                 MouseScrollDelta::LineDelta(_, y) => *y,
                 MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / constants::input::SCROLL_PIXEL_DIVISOR,
             };
-            params.zoom_by(scroll * constants::input::ZOOM_SCROLL_MULTIPLIER);
+            let factor = (1.0 + scroll * constants::input::ZOOM_SCROLL_MULTIPLIER).max(0.1) as f64;
+            // Eased over the next frames by the animator, toward the cursor or the visible center
+            let anchor = input.zoom_anchor(&Viewport::for_screen(params, gpu_size));
+            animator.zoom_at(params, factor, anchor, animator.now());
+            false
+        }
+        WindowEvent::Touch(touch) => {
+            let (x, y) = (touch.location.x as f32, touch.location.y as f32);
+            let gesture = input.handle_touch(touch.id, touch.phase, x, y);
+            match touch.phase {
+                TouchPhase::Started => animator.grab(),
+                TouchPhase::Ended if input.touch_count() == 0 => animator.release(animator.now()),
+                _ => {}
+            }
+            match gesture {
+                Some(gesture) => {
+                    let pan = [gesture.pan.0 as f64, gesture.pan.1 as f64];
+                    let mut viewport = Viewport::for_screen(params, gpu_size);
+                    gesture.apply(&mut viewport);
+                    viewport.apply(params);
+                    // Only one-finger drags carry momentum
+                    if input.touch_count() == 1 {
+                        animator.track_drag(pan, animator.now());
+                    }
+                    true
+                }
+                None => false,
+            }
+        }
+        WindowEvent::PinchGesture { delta, .. } => {
+            // Trackpad pinch: zoom toward the cursor like the wheel does
+            let factor = (1.0 + *delta).max(0.1);
+            let mut viewport = Viewport::for_screen(params, gpu_size);
+            viewport.zoom_at(factor, input.zoom_anchor(&viewport));
+            viewport.apply(params);
             true
         }
+        WindowEvent::CursorLeft { .. } => {
+            input.handle_cursor_left();
+            false
+        }
         _ => false,
     }
 }
 
+/// Carry out a keyboard shortcut
+/// Returns true if the renderer should be marked dirty
+fn apply_key_action(action: Action, ui: &mut UiState, params: &mut FractalParams, gpu_size: (u32, u32)) -> bool {
+    use crate::constants::input::{KEY_ITERATION_FACTOR, KEY_PAN_FRACTION, KEY_ROTATE_STEP, KEY_ZOOM_FACTOR};
+    use crate::constants::limits::{MAX_ITERATION_BIAS, MIN_ITERATION_BIAS};
+    use crate::constants::ui::MIN_ITERATIONS;
+
+    let mut viewport = Viewport::for_screen(params, gpu_size);
+    let step = viewport.visible_size()[1] * KEY_PAN_FRACTION;
+    match action {
+        // Panning moves the content, so moving the view left drags the content right
+        Action::PanLeft => viewport.pan(step, 0.0),
+        Action::PanRight => viewport.pan(-step, 0.0),
+        Action::PanUp => viewport.pan(0.0, step),
+        Action::PanDown => viewport.pan(0.0, -step),
+        Action::ZoomIn => viewport.zoom_by(KEY_ZOOM_FACTOR),
+        Action::ZoomOut => viewport.zoom_by(1.0 / KEY_ZOOM_FACTOR),
+        Action::RotateLeft => viewport.rotate_by(KEY_ROTATE_STEP),
+        Action::RotateRight => viewport.rotate_by(-KEY_ROTATE_STEP),
+        Action::Reset => {
+            params.reset();
+            return true;
+        }
+        Action::NextFractal => {
+            ui.next_fractal_type(params);
+            return true;
+        }
+        Action::NextColorScheme => {
+            ui.next_color_scheme(params);
+            return true;
+        }
+        Action::MoreIterations | Action::FewerIterations => {
+            let factor = if action == Action::MoreIterations {
+                KEY_ITERATION_FACTOR
+            } else {
+                1.0 / KEY_ITERATION_FACTOR
+            };
+            // In auto mode the keys lean on the automatic count instead of replacing it
+            if params.auto_iterations() {
+                let bias = params.iteration_bias * factor;
+                params.iteration_bias = bias.clamp(MIN_ITERATION_BIAS, MAX_ITERATION_BIAS);
+                return true;
+            }
+            let max_iter = (params.max_iter as f32 * factor).round();
+            params.max_iter = max_iter.clamp(MIN_ITERATIONS, params.max_iterations() as f32) as u32;
+            return true;
+        }
+        Action::ToggleHelp => {
+            ui.toggle_help();
+            return false;
+        }
+        Action::Undo => return ui.undo(params),
+        Action::Redo => return ui.redo(params),
+    }
+    viewport.apply(params);
+    true
+}
+
+/// Render the current view to a `width` x `height` PNG named after the current time
+/// The panel offset is dropped so the image is centered on the view center.
+/// Rendering happens off the frame loop; the outcome arrives as a status event
+fn save_image(params: &FractalParams, ui: &mut UiState, width: u32, height: u32) {
+    let mut export_params = *params;
+    export_params.ui_offset = 0.0;
+    export_params.ui_offset_y = 0.0;
+    let file_name = format!("fractal-madness-{}.png", files::timestamp());
+
+    ui.set_status(format!("Rendering {width}x{height}..."));
+
+    let task = async move {
+        let status = match export::render_png(&export_params, width, height)
+            .await
+            .and_then(|png| files::save_bytes(&file_name, &png, "image/png"))
+        {
+            Ok(path) => format!("Saved {path}"),
+            Err(e) => e,
+        };
+        files::push_event(FileEvent::Status(status));
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(move || pollster::block_on(task));
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_futures::spawn_local(task);
+}
+
+/// Write the current view to a JSON scene file named after the current time
+fn save_scene(params: &FractalParams, ui: &mut UiState) {
+    let file_name = format!("fractal-madness-{}.json", files::timestamp());
+    let status = match Scene::new(params)
+        .to_json()
+        .and_then(|json| files::save_bytes(&file_name, json.as_bytes(), "application/json"))
+    {
+        Ok(path) => format!("Saved {path}"),
+        Err(e) => e,
+    };
+    ui.set_status(status);
+}
+
+/// Apply a file the user opened (picker or drag and drop)
+/// PNGs with embedded parameters, JSON/RON scenes, exported bookmarks and preset packs
+/// are accepted, and all of them are validated
+/// Returns true if params changed
+fn open_file(name: &str, bytes: &[u8], params: &mut FractalParams, ui: &mut UiState) -> bool {
+    // Exported bookmarks and preset packs are added to the panel instead of changing the view;
+    // their top-level key tells them apart from scenes. The content decides, since downloads
+    // may be named after URLs without an extension
+    let document = std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|text| Some((text, serde_json::from_str::<serde_json::Value>(text).ok()?)));
+    if let Some((text, value)) = &document {
+        if value.get("bookmarks").is_some() {
+            match Bookmarks::from_json(text) {
+                Ok(imported) => ui.import_bookmarks(imported, name),
+                Err(e) => ui.set_status(e),
+            }
+            return false;
+        }
+        if value.get("presets").is_some() {
+            match presets::parse_pack(text) {
+                Ok(pack) => ui.add_preset_pack(pack, name),
+                Err(e) => ui.set_status(e),
+            }
+            return false;
+        }
+    }
+
+    let scene = match files::extension(name).as_deref() {
+        Some("png") => export::read_png_params(bytes).map(|loaded| Scene::new(&loaded)),
+        Some("json" | "ron") => std::str::from_utf8(bytes)
+            .map_err(|_| format!("{name} is not a text file"))
+            .and_then(|text| Scene::from_file(name, text)),
+        _ => match document {
+            Some((text, _)) => Scene::from_json(text),
+            None => Err(format!("Unsupported file: {name}")),
+        },
+    };
+
+    match scene.and_then(|scene| scene.validate()) {
+        Ok(loaded) => {
+            // Keep the per-frame layout fields of the live view
+            *params = FractalParams {
+                resolution: params.resolution,
+                ui_offset: params.ui_offset,
+                ui_offset_y: params.ui_offset_y,
+                ..loaded
+            };
+            ui.set_status(format!("Loaded view from {name}"));
+            true
+        }
+        Err(e) => {
+            ui.set_status(e);
+            false
+        }
+    }
+}
+
+/// Carry out panel actions and apply finished file operations
+/// Returns true if params changed
+fn process_app_events(
+    params: &mut FractalParams,
+    ui: &mut UiState,
+    animator: &mut Animator,
+    size: (u32, u32),
+) -> bool {
+    let mut changed = false;
+    for action in ui.take_actions() {
+        match action {
+            UiAction::SaveImage => save_image(params, ui, size.0, size.1),
+            UiAction::SavePoster { width, height } => save_image(params, ui, width, height),
+            UiAction::SaveScene => save_scene(params, ui),
+            UiAction::FlyTo { target, duration } => {
+                animator.fly_to(params, &target, duration as f64, animator.now());
+                changed = true;
+            }
+        }
+    }
+
+    for event in files::drain_events() {
+        match event {
+            FileEvent::Opened { name, bytes } => changed |= open_file(&name, &bytes, params, ui),
+            FileEvent::Status(status) => ui.set_status(status),
+        }
+    }
+    changed
+}
+
 /// Render a single frame to the screen
 fn render_frame(
     gpu: &WebGpuState,
@@ -99,7 +395,10 @@ struct App {
     renderer: FractalRenderer,
     ui: UiState,
     input: InputState,
+    animator: Animator,
+    iterations: AutoIterations,
     params: FractalParams,
+    hash_sync: deeplink::HashSync,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -116,7 +415,14 @@ impl ApplicationHandler for App {
     ) {
         let consumed = self.ui.handle_window_event(&self.window, &event);
 
-        if !consumed && handle_input_event(&event, &mut self.input, &mut self.params, self.gpu.size) {
+        if !consumed && handle_input_event(
+            &event,
+            &mut self.input,
+            &mut self.ui,
+            &mut self.animator,
+            &mut self.params,
+            self.gpu.size,
+        ) {
             self.renderer.mark_dirty();
         }
 
@@ -134,20 +440,36 @@ impl ApplicationHandler for App {
                 // Update resolution for shader aspect ratio
                 self.params.resolution = [self.gpu.size.0 as f32, self.gpu.size.1 as f32];
 
-                let ui_changed = self.ui.prepare(&self.window, &mut self.params);
+                let now = self.animator.now();
+                if self.animator.tick(&mut self.params, self.gpu.size, now) {
+                    self.renderer.mark_dirty();
+                }
+
+                self.ui.set_selection(self.input.selection().map(|selection| selection.bounds()));
+                let ui_changed = self.ui.prepare(&self.window, &mut self.params, self.input.keymap());
                 if ui_changed {
                     self.renderer.mark_dirty();
                 }
 
-                // Calculate ui_offset to center fractal in visible area (excluding panel)
-                let scale_factor = self.window.scale_factor() as f32;
-                let panel_width_physical = self.ui.get_panel_width() * scale_factor;
-                let canvas_width = self.gpu.size.0 as f32;
-                let canvas_height = self.gpu.size.1 as f32;
-                let panel_proportion = panel_width_physical / canvas_width;
-                let aspect = canvas_width / canvas_height;
-                self.params.ui_offset = -panel_proportion * aspect;
-                self.params.ui_offset_y = 0.0;
+                if process_app_events(&mut self.params, &mut self.ui, &mut self.animator, self.gpu.size) {
+                    self.renderer.mark_dirty();
+                }
+
+                if self.hash_sync.sync(&mut self.params) {
+                    self.renderer.mark_dirty();
+                }
+
+                // Center the fractal in the visible area (excluding panel)
+                let scale_factor = self.window.scale_factor();
+                let mut viewport = Viewport::for_screen(&self.params, self.gpu.size);
+                viewport.set_panel_width(self.ui.get_panel_width() as f64 * scale_factor);
+                viewport.apply(&mut self.params);
+
+                let busy = self.input.is_interacting() || self.animator.is_active();
+                if self.iterations.update(&mut self.params, busy, self.renderer.orbits()) {
+                    self.renderer.mark_dirty();
+                }
+                self.ui.record_history(&self.params, busy, now);
 
                 render_frame(&self.gpu, &mut self.renderer, &mut self.ui, &self.params, &self.window);
                 self.window.request_redraw();
@@ -169,6 +491,8 @@ struct NativeApp {
     renderer: FractalRenderer,
     ui: UiState,
     input: InputState,
+    animator: Animator,
+    iterations: AutoIterations,
     params: FractalParams,
 }
 
@@ -186,27 +510,54 @@ impl ApplicationHandler for NativeApp {
     ) {
         let consumed = self.ui.handle_window_event(&self.window, &event);
 
-        if !consumed && handle_input_event(&event, &mut self.input, &mut self.params, self.gpu.size) {
+        if !consumed && handle_input_event(
+            &event,
+            &mut self.input,
+            &mut self.ui,
+            &mut self.animator,
+            &mut self.params,
+            self.gpu.size,
+        ) {
             self.renderer.mark_dirty();
         }
 
         match event {
             WindowEvent::CloseRequested => {
+                self.ui.store_views();
                 event_loop.exit();
             }
             WindowEvent::Resized(new_size) => {
                 self.gpu.resize(new_size.width, new_size.height);
                 self.renderer.resize(&self.gpu.device, new_size.width, new_size.height);
             }
+            WindowEvent::DroppedFile(path) => {
+                files::open_path(&path);
+            }
             WindowEvent::RedrawRequested => {
                 // Update resolution for shader aspect ratio
                 self.params.resolution = [self.gpu.size.0 as f32, self.gpu.size.1 as f32];
 
-                let ui_changed = self.ui.prepare(&self.window, &mut self.params);
+                let now = self.animator.now();
+                if self.animator.tick(&mut self.params, self.gpu.size, now) {
+                    self.renderer.mark_dirty();
+                }
+
+                self.ui.set_selection(self.input.selection().map(|selection| selection.bounds()));
+                let ui_changed = self.ui.prepare(&self.window, &mut self.params, self.input.keymap());
                 if ui_changed {
                     self.renderer.mark_dirty();
                 }
 
+                if process_app_events(&mut self.params, &mut self.ui, &mut self.animator, self.gpu.size) {
+                    self.renderer.mark_dirty();
+                }
+
+                let busy = self.input.is_interacting() || self.animator.is_active();
+                if self.iterations.update(&mut self.params, busy, self.renderer.orbits()) {
+                    self.renderer.mark_dirty();
+                }
+                self.ui.record_history(&self.params, busy, now);
+
                 render_frame(&self.gpu, &mut self.renderer, &mut self.ui, &self.params, &self.window);
                 self.window.request_redraw();
             }
@@ -311,8 +662,16 @@ async fn run_inner() -> Result<(), String> {
     let mut ui = UiState::new(&gpu.device, gpu.format, &window);
     #[allow(unused_mut)]
     let mut input = InputState::new();
+    // Pick up where the last session left off
     #[allow(unused_mut)]
-    let mut params = FractalParams::default();
+    let mut params = ui.restored_view();
+
+    // Restore a shared view before the first frame; a missing or bad hash keeps the default
+    #[cfg(target_arch = "wasm32")]
+    if let Some(linked) = deeplink::read_location() {
+        log::info!("Restoring view from URL hash");
+        params = linked;
+    }
 
     log::info!("Initialization complete, starting render loop");
 
@@ -320,13 +679,17 @@ async fn run_inner() -> Result<(), String> {
     {
         use winit::platform::web::EventLoopExtWebSys;
 
+        let hash_sync = deeplink::HashSync::new(&params);
         let app = App {
             window,
             gpu,
             renderer,
             ui,
             input,
+            animator: Animator::new(),
+            iterations: AutoIterations::new(),
             params,
+            hash_sync,
         };
         event_loop.spawn_app(app);
     }
@@ -339,6 +702,8 @@ async fn run_inner() -> Result<(), String> {
             renderer,
             ui,
             input,
+            animator: Animator::new(),
+            iterations: AutoIterations::new(),
             params,
         };
         event_loop
@@ -0,0 +1,503 @@
+//! Perturbation rendering for zooms past what double-single can resolve
+//! One reference orbit `Z_n` is iterated on the CPU in fixed point at the view center; each pixel
+//! then only iterates its difference from it, `d_{n+1} = f(Z_n + d_n) - f(Z_n) + d_0`, which f32
+//! holds at any depth. Deltas are kept as `w * 2^-k` with `w` near 1 so they never underflow,
+//! and a pixel whose orbit drifts away from the reference is rebased onto the start of it.
+//! Pans and zooms keep the orbit for as long as its point stays on screen and it is precise and
+//! long enough; pixels then start from their delta to that point rather than to the view center.
+//! New orbits are computed off the frame loop (see [`ReferenceOrbits`]), and the previous one is
+//! rendered against until they are ready.
+//! The orbit is uploaded as a float texture rather than a storage buffer: WebGL2 has no
+//! storage buffers in fragment shaders
+//!
+//! For Mandelbrot the first iterations are skipped altogether: while deltas are small, `d_n` is
+//! close to a polynomial in `d_0` whose coefficients follow from the reference orbit alone
+//! (series approximation). The polynomial is trusted for as long as it tracks the exactly
+//! iterated deltas of probe points on the edges of the view
+
+use std::sync::Arc;
+
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::ui;
+use crate::fixed::Fixed;
+use crate::fractal::{Coordinate, FractalParams, FractalType};
+use crate::viewport::Viewport;
+
+/// Reference orbit texture width in texels (the WebGL2 downlevel limit)
+/// Must match `REFERENCE_WIDTH` in `fractal.wgsl`
+pub const REFERENCE_WIDTH: u32 = 2048;
+/// Reference orbit texture height, enough for the longest orbit a perturbation view allows
+/// (must stay within the WebGL2 texture size limit, which is also 2048)
+pub const REFERENCE_ROWS: u32 = (ui::MAX_DEEP_ITERATIONS as u32 + 1).div_ceil(REFERENCE_WIDTH);
+/// Bits carried past the pixel size so reference rounding never shows
+const GUARD_BITS: f64 = 64.0;
+/// Terms of the series approximation
+/// Must match `SERIES_TERMS` in `fractal.wgsl`
+pub const SERIES_TERMS: usize = 8;
+/// Largest error the series may leave in a probe, as a fraction of the pixel spacing there
+const SERIES_TOLERANCE: f64 = 1e-3;
+
+/// Perturbation uniform (binding 1 in `fractal.wgsl`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Pod, Zeroable)]
+pub struct PerturbationParams {
+    /// Points in the reference orbit texture; 0 turns perturbation off
+    pub ref_len: u32,
+    /// Pixel deltas start out scaled by `2^scale_exp`
+    pub scale_exp: i32,
+    /// `2^scale_exp / zoom`: maps a rotated NDC offset to a scaled delta
+    pub pixel_scale: f32,
+    /// Iterations skipped by the series approximation; 0 starts every pixel at `Z_0`
+    pub skip: u32,
+    /// The series gives deltas scaled by `2^series_exp`
+    pub series_exp: i32,
+    pub _padding: u32,
+    /// Scaled delta of the view center from the reference, added to every pixel's start delta
+    pub reference_offset: [f32; 2],
+    /// Series coefficients of `w0, w0^2, ...` for the scaled start delta `w0`, two per row
+    pub series: [[f32; 4]; SERIES_TERMS / 2],
+}
+
+impl PerturbationParams {
+    pub fn new(zoom: f64, ref_len: usize) -> Self {
+        let scale_exp = zoom.log2().floor() as i32;
+        Self {
+            ref_len: ref_len as u32,
+            scale_exp,
+            pixel_scale: (2.0_f64.powi(scale_exp) / zoom) as f32,
+            ..Self::default()
+        }
+    }
+
+    /// Skip the iterations `series` covers
+    pub fn with_series(mut self, series: &Series) -> Self {
+        if series.skip == 0 {
+            return self;
+        }
+        // The linear term grows with every iteration; shifting it back near 1 keeps every
+        // coefficient within f32 range and the result a delta of ordinary size
+        let linear = series.coefficients[0][0].hypot(series.coefficients[0][1]);
+        let shift = (linear.log2().floor() as i32).clamp(0, self.scale_exp.max(0));
+        let factor = 2.0_f64.powi(-shift);
+        for (i, c) in series.coefficients.iter().enumerate() {
+            let row = &mut self.series[i / 2];
+            row[2 * (i % 2)] = (c[0] * factor) as f32;
+            row[2 * (i % 2) + 1] = (c[1] * factor) as f32;
+        }
+        self.skip = series.skip;
+        self.series_exp = self.scale_exp - shift;
+        self
+    }
+}
+
+fn cmul(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] * b[0] - a[1] * b[1], a[0] * b[1] + a[1] * b[0]]
+}
+
+fn cadd(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+/// Polynomial in a pixel's scaled start delta `w0` that gives its scaled delta after `skip`
+/// Mandelbrot iterations
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Series {
+    pub skip: u32,
+    /// Coefficient of `w0^(i + 1)` at index `i`
+    pub coefficients: [[f64; 2]; SERIES_TERMS],
+}
+
+impl Series {
+    pub fn eval(&self, w0: [f64; 2]) -> [f64; 2] {
+        // Horner's rule; there is no constant term
+        self.coefficients
+            .iter()
+            .rev()
+            .fold([0.0, 0.0], |acc, &c| cmul(cadd(acc, c), w0))
+    }
+}
+
+/// Series approximation of the Mandelbrot perturbation `w_{n+1} = 2 Z_n w_n + 2^-scale_exp w_n^2 + w0`
+/// around `orbit`, for scaled start deltas `w0 = d_0 * 2^scale_exp`
+/// Substituting `w_n = sum a_i w0^i` gives `a_1 <- 2 Z_n a_1 + 1` and
+/// `a_i <- 2 Z_n a_i + 2^-scale_exp sum_{j + l = i} a_j a_l`. Iteration stops before the series
+/// misses the exactly iterated delta of any of the `probes` by more than a small fraction of
+/// `pixel` (the spacing of neighbouring pixels in `w0`, carried forward by `a_1`), or before a
+/// probe would escape or be rebased. Never skips to the end of the orbit or to `max_iter`
+pub fn series_approximation(
+    orbit: &[[f32; 2]],
+    scale_exp: i32,
+    probes: &[[f64; 2]],
+    pixel: f64,
+    max_iter: u32,
+    escape_radius: f32,
+) -> Series {
+    let scale = 2.0_f64.powi(-scale_exp);
+    let unscale = 2.0_f64.powi(scale_exp);
+    let escape2 = escape_radius as f64 * escape_radius as f64;
+    let limit = (orbit.len().saturating_sub(2)).min(max_iter.saturating_sub(1) as usize);
+
+    let mut best = Series::default();
+    let mut coefficients = [[0.0; 2]; SERIES_TERMS];
+    let mut deltas = vec![[0.0; 2]; probes.len()];
+    for n in 0..limit {
+        let z = orbit[n].map(f64::from);
+        let two_z = [2.0 * z[0], 2.0 * z[1]];
+
+        let mut next = [[0.0; 2]; SERIES_TERMS];
+        for (i, c) in next.iter_mut().enumerate() {
+            let square = (0..i).fold([0.0, 0.0], |acc, j| cadd(acc, cmul(coefficients[j], coefficients[i - 1 - j])));
+            *c = cadd(cmul(two_z, coefficients[i]), [square[0] * scale, square[1] * scale]);
+        }
+        next[0][0] += 1.0;
+        coefficients = next;
+        let candidate = Series { skip: n as u32 + 1, coefficients };
+
+        let zr = orbit[n + 1].map(f64::from);
+        let allowed = SERIES_TOLERANCE * pixel * coefficients[0][0].hypot(coefficients[0][1]);
+        for (w, &w0) in deltas.iter_mut().zip(probes) {
+            let square = cmul(*w, *w);
+            *w = cadd(cadd(cmul(two_z, *w), [square[0] * scale, square[1] * scale]), w0);
+
+            let error = cadd(candidate.eval(w0), [-w[0], -w[1]]);
+            let error = error[0].hypot(error[1]);
+            if error > allowed || !error.is_finite() {
+                return best;
+            }
+            let full = [zr[0] + w[0] * scale, zr[1] + w[1] * scale];
+            let rebased = [zr[0] * unscale + w[0], zr[1] * unscale + w[1]];
+            let near_zero = rebased[0].hypot(rebased[1]) < w[0].hypot(w[1]);
+            if full[0] * full[0] + full[1] * full[1] >= escape2 || near_zero {
+                return best;
+            }
+        }
+        best = candidate;
+    }
+    best
+}
+
+/// Iterate the power-2 formula of `fractal_type` at `center` in fixed point
+/// Returns `Z_0 = 0, Z_1 = center, ...` rounded to f32, up to and including the first point
+/// past `escape_radius`, or `max_iter + 1` points if it never escapes
+pub fn reference_orbit<const N: usize>(
+    fractal_type: FractalType,
+    center: [Fixed<N>; 2],
+    max_iter: u32,
+    escape_radius: f32,
+) -> Vec<[f32; 2]> {
+    let escape2 = escape_radius as f64 * escape_radius as f64;
+    let (mut x, mut y) = (Fixed::ZERO, Fixed::ZERO);
+    let mut points = Vec::with_capacity(max_iter as usize + 1);
+    loop {
+        let (xf, yf) = (x.to_f64(), y.to_f64());
+        points.push([xf as f32, yf as f32]);
+        if points.len() > max_iter as usize || xf * xf + yf * yf >= escape2 {
+            return points;
+        }
+
+        match fractal_type {
+            FractalType::BurningShip => (x, y) = (x.abs(), y.abs()),
+            FractalType::Tricorn => y = -y,
+            _ => {}
+        }
+        let xy = x * y;
+        (x, y) = (x * x - y * y + center[0], xy + xy + center[1]);
+    }
+}
+
+/// Everything a reference orbit depends on
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct OrbitKey {
+    center: [Coordinate; 2],
+    fractal_type: FractalType,
+    max_iter: u32,
+    escape_radius: f32,
+    limbs: usize,
+}
+
+impl OrbitKey {
+    fn new(params: &FractalParams) -> Self {
+        // Enough fraction bits for the pixel size plus guard bits
+        let bits = params.zoom.log2().max(0.0) + GUARD_BITS;
+        let limbs = match bits {
+            b if b <= 96.0 => 4,
+            b if b <= 224.0 => 8,
+            _ => 16,
+        };
+        Self {
+            center: params.center,
+            fractal_type: params.get_fractal_type(),
+            // The rest of a longer orbit would not fit in the texture
+            max_iter: params.max_iter.min(REFERENCE_WIDTH * REFERENCE_ROWS - 1),
+            escape_radius: params.escape_radius,
+            limbs,
+        }
+    }
+
+    fn orbit(&self) -> Vec<[f32; 2]> {
+        let (ft, max_iter, radius) = (self.fractal_type, self.max_iter, self.escape_radius);
+        match self.limbs {
+            4 => reference_orbit(ft, self.center.map(|c| c.resize::<4>()), max_iter, radius),
+            8 => reference_orbit(ft, self.center.map(|c| c.resize::<8>()), max_iter, radius),
+            _ => reference_orbit(ft, self.center.map(|c| c.resize::<16>()), max_iter, radius),
+        }
+    }
+
+    /// Whether an orbit computed for this key, `len` points long, will do for `params`: the same
+    /// formula, at least as many limbs, long enough (or escaped before its end), and its point on screen
+    fn fits(&self, len: usize, params: &FractalParams) -> bool {
+        let wanted = Self::new(params);
+        let escaped = len <= self.max_iter as usize;
+        if !self.same_formula(&wanted) || self.limbs < wanted.limbs || (self.max_iter < wanted.max_iter && !escaped) {
+            return false;
+        }
+        let viewport = Viewport::from_params(params);
+        let screen = viewport.coordinate_to_screen(self.center);
+        (0..2).all(|i| (0.0..=viewport.resolution[i]).contains(&screen[i]))
+    }
+
+    fn same_formula(&self, other: &Self) -> bool {
+        self.fractal_type == other.fractal_type && self.escape_radius == other.escape_radius
+    }
+}
+
+/// The reference orbit of a view, remembering what it was computed for
+pub struct ReferenceOrbit {
+    pub points: Vec<[f32; 2]>,
+    key: OrbitKey,
+}
+
+impl ReferenceOrbit {
+    /// Compute the orbit at the center of `params`
+    pub fn new(params: &FractalParams) -> Self {
+        let key = OrbitKey::new(params);
+        Self { points: key.orbit(), key }
+    }
+
+    /// Whether this orbit can be reused for `params`: the reference point is still on screen,
+    /// and the orbit is as precise and at least as long as a new one would be
+    pub fn matches(&self, params: &FractalParams) -> bool {
+        self.key.fits(self.points.len(), params)
+    }
+
+    /// Whether `params` iterates the formula of this orbit, so the orbit can stand in for a
+    /// matching one while that is computed; pixels far from it are rebased more often
+    pub fn same_formula(&self, params: &FractalParams) -> bool {
+        self.key.same_formula(&OrbitKey::new(params))
+    }
+
+    /// The perturbation uniform for rendering `params` against this orbit, with as many
+    /// iterations skipped as the series approximation allows for the whole view
+    pub fn uniform(&self, params: &FractalParams) -> PerturbationParams {
+        let mut uniform = PerturbationParams::new(params.zoom, self.points.len());
+        // Pixel deltas are taken from the reference, which stays put while the view pans
+        let scale = 2.0_f64.powi(uniform.scale_exp);
+        let shift = [0, 1].map(|i| (params.center[i] - self.key.center[i]).to_f64() * scale);
+        uniform.reference_offset = shift.map(|v| v as f32);
+        if self.key.fractal_type != FractalType::Mandelbrot {
+            return uniform;
+        }
+
+        // Corners, edge midpoints and the middle of the view
+        let viewport = Viewport::from_params(params);
+        let to_scaled = params.zoom * uniform.pixel_scale as f64;
+        let probes: Vec<[f64; 2]> = (0..9)
+            .map(|i| {
+                let screen = [i % 3, i / 3].map(|j| j as f64 / 2.0);
+                let d = viewport.screen_to_offset([screen[0] * viewport.resolution[0], screen[1] * viewport.resolution[1]]);
+                [d[0] * to_scaled + shift[0], d[1] * to_scaled + shift[1]]
+            })
+            .collect();
+        let pixel = viewport.pixel_size() * to_scaled;
+        let series = series_approximation(
+            &self.points,
+            uniform.scale_exp,
+            &probes,
+            pixel,
+            params.max_iter,
+            self.key.escape_radius,
+        );
+        uniform.with_series(&series)
+    }
+}
+
+/// A [`ReferenceOrbit`] being computed off the frame loop: on a thread natively, in the page's
+/// orbit worker on the web (see [`set_orbit_worker`]), or on the spot if the page has none
+pub struct OrbitJob {
+    key: OrbitKey,
+    #[cfg(not(target_arch = "wasm32"))]
+    receiver: std::sync::mpsc::Receiver<Vec<[f32; 2]>>,
+    #[cfg(target_arch = "wasm32")]
+    id: Option<u32>,
+}
+
+impl OrbitJob {
+    pub fn start(params: &FractalParams) -> Self {
+        let key = OrbitKey::new(params);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                // The renderer may have been dropped meanwhile; nobody is left to tell
+                let _ = sender.send(key.orbit());
+            });
+            Self { key, receiver }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        Self { key, id: worker::post(&key) }
+    }
+
+    /// Whether the orbit will do for `params` once it is ready (see [`ReferenceOrbit::matches`])
+    pub fn matches(&self, params: &FractalParams) -> bool {
+        self.key.fits(usize::MAX, params)
+    }
+
+    /// The orbit, once, when the job has finished
+    pub fn poll(&mut self) -> Option<ReferenceOrbit> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let points = self.receiver.try_recv().ok()?;
+
+        #[cfg(target_arch = "wasm32")]
+        let points = match self.id.map(worker::take) {
+            Some(Some(points)) if !points.is_empty() => points,
+            Some(None) => return None,
+            _ => self.key.orbit(),
+        };
+
+        Some(ReferenceOrbit { points, key: self.key })
+    }
+}
+
+/// The latest reference orbit and the one being computed to replace it
+/// Shared by the renderer and the automatic iteration count; one orbit is computed at a time
+#[derive(Default)]
+pub struct ReferenceOrbits {
+    current: Option<Arc<ReferenceOrbit>>,
+    pending: Option<OrbitJob>,
+    /// Compute orbits on the spot, for one-off renders that cannot wait for them
+    blocking: bool,
+}
+
+impl ReferenceOrbits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Orbits that are computed on the spot when requested
+    pub fn blocking() -> Self {
+        Self { blocking: true, ..Self::default() }
+    }
+
+    /// An orbit that [`ReferenceOrbit::matches`] `params`, if there is one yet
+    /// Otherwise one is started once the job underway, if any, has finished
+    pub fn request(&mut self, params: &FractalParams) -> Option<Arc<ReferenceOrbit>> {
+        if let Some(orbit) = self.pending.as_mut().and_then(OrbitJob::poll) {
+            self.current = Some(Arc::new(orbit));
+            self.pending = None;
+        }
+        if let Some(current) = self.current.as_ref().filter(|orbit| orbit.matches(params)) {
+            return Some(current.clone());
+        }
+
+        if self.blocking {
+            let orbit = Arc::new(ReferenceOrbit::new(params));
+            self.current = Some(orbit.clone());
+            return Some(orbit);
+        }
+        if self.pending.is_none() {
+            self.pending = Some(OrbitJob::start(params));
+        }
+        None
+    }
+
+    /// The latest orbit, if it iterates the formula of `params` (see [`ReferenceOrbit::same_formula`])
+    pub fn latest(&self, params: &FractalParams) -> Option<Arc<ReferenceOrbit>> {
+        self.current.clone().filter(|orbit| orbit.same_formula(params))
+    }
+}
+
+/// Register the Web Worker that computes reference orbits (`web/orbit-worker.ts`)
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn set_orbit_worker(orbit_worker: web_sys::Worker) {
+    worker::register(orbit_worker);
+}
+
+/// Compute the orbit for a request posted by [`OrbitJob`]; runs in the orbit worker
+/// Returns the points as interleaved (re, im) pairs, or nothing if the request is malformed
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn compute_reference_orbit(request: &str) -> Vec<f32> {
+    match serde_json::from_str::<OrbitKey>(request) {
+        Ok(key) => key.orbit().into_iter().flatten().collect(),
+        Err(e) => {
+            log::error!("Invalid orbit request: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Messages to and from the orbit worker: `[id, request]` out, `[id, points]` back
+#[cfg(target_arch = "wasm32")]
+mod worker {
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use super::OrbitKey;
+
+    thread_local! {
+        static WORKER: RefCell<Option<web_sys::Worker>> = const { RefCell::new(None) };
+    }
+    static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+    /// Orbits the worker sent back, by job id
+    static FINISHED: Mutex<Vec<(u32, Vec<[f32; 2]>)>> = Mutex::new(Vec::new());
+
+    pub fn register(worker: web_sys::Worker) {
+        let on_message = Closure::<dyn FnMut(web_sys::MessageEvent)>::new(|event: web_sys::MessageEvent| {
+            let message: js_sys::Array = event.data().unchecked_into();
+            let id = message.get(0).as_f64().unwrap_or_default() as u32;
+            let flat = js_sys::Float32Array::new(&message.get(1)).to_vec();
+            let points = flat.as_chunks::<2>().0.to_vec();
+            if let Ok(mut finished) = FINISHED.lock() {
+                finished.push((id, points));
+            }
+        });
+        worker.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        // The worker lives as long as the page
+        on_message.forget();
+        WORKER.with(|slot| *slot.borrow_mut() = Some(worker));
+    }
+
+    /// Send `key` to the worker; the job id, or `None` if no worker is registered
+    pub fn post(key: &OrbitKey) -> Option<u32> {
+        let request = serde_json::to_string(key).ok()?;
+        WORKER.with(|slot| {
+            let slot = slot.borrow();
+            let worker = slot.as_ref()?;
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let message = js_sys::Array::of2(&JsValue::from(id), &JsValue::from_str(&request));
+            match worker.post_message(&message) {
+                Ok(()) => Some(id),
+                Err(e) => {
+                    log::warn!("Orbit worker unavailable ({:?}), computing on the spot", e);
+                    None
+                }
+            }
+        })
+    }
+
+    /// The points for job `id`, once the worker has sent them; empty if the request failed
+    pub fn take(id: u32) -> Option<Vec<[f32; 2]>> {
+        let mut finished = FINISHED.lock().ok()?;
+        let index = finished.iter().position(|(job, _)| *job == id)?;
+        Some(finished.swap_remove(index).1)
+    }
+}
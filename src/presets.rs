@@ -0,0 +1,108 @@
+//! Location presets loaded from data instead of compiled-in tables
+//! The built-in list is embedded from `assets/presets.json`; extra packs in the same format
+//! can be dropped in at runtime and are validated before they are offered in the panel
+
+use serde::Deserialize;
+
+use crate::constants::limits;
+use crate::files;
+use crate::fractal::FractalType;
+
+pub use crate::fractal::LocationPreset;
+
+/// Current preset pack version
+pub const PRESETS_VERSION: u32 = 1;
+
+/// Presets shipped with the app
+const BUILTIN_PRESETS: &str = include_str!("../assets/presets.json");
+
+/// Name of the preset pack picked up from the user's config on startup
+const USER_PACK_NAME: &str = "presets";
+
+/// A preset pack file, tagged with the format version it was written with
+#[derive(Deserialize)]
+struct Document {
+    version: u32,
+    presets: Vec<LocationPreset>,
+}
+
+/// Parse and validate a preset pack
+/// Fractal types must be one of the [`FractalType`] names; values must be finite and within the
+/// ranges the UI offers. Unlike scene files nothing is clamped: a bad pack is rejected as a whole
+pub fn parse_pack(text: &str) -> Result<Vec<LocationPreset>, String> {
+    let document: Document = serde_json::from_str(text).map_err(|e| format!("Invalid preset pack: {e}"))?;
+    if document.version == 0 || document.version > PRESETS_VERSION {
+        return Err(format!("Unsupported preset pack version {}", document.version));
+    }
+    for preset in &document.presets {
+        validate(preset).map_err(|e| format!("Preset \"{}\": {e}", preset.name))?;
+    }
+    Ok(document.presets)
+}
+
+fn validate(preset: &LocationPreset) -> Result<(), String> {
+    if preset.name.trim().is_empty() {
+        return Err("name is empty".to_string());
+    }
+    if !(limits::MIN_ZOOM..=limits::MAX_ZOOM).contains(&preset.zoom) {
+        return Err(format!("zoom {} is out of range", preset.zoom));
+    }
+    if let Some(power) = preset.power {
+        if !(limits::MIN_POWER..=limits::MAX_POWER).contains(&power) {
+            return Err(format!("power {power} is out of range"));
+        }
+    }
+    if let Some(c) = preset.julia_c {
+        let max_c = limits::MAX_JULIA_C;
+        if !c.iter().all(|v| (-max_c..=max_c).contains(v)) {
+            return Err(format!("julia_c {c:?} is out of range"));
+        }
+    }
+    Ok(())
+}
+
+/// Every preset on offer: the built-in list followed by any packs loaded since
+#[derive(Debug, Clone)]
+pub struct PresetLibrary {
+    presets: Vec<LocationPreset>,
+}
+
+impl PresetLibrary {
+    /// The presets embedded in the binary
+    pub fn builtin() -> Self {
+        let presets = parse_pack(BUILTIN_PRESETS).expect("built-in presets are valid");
+        Self { presets }
+    }
+
+    /// The built-in presets plus the user's pack from the config directory, if there is one
+    pub fn load() -> Self {
+        let mut library = Self::builtin();
+        if let Some(text) = files::read_config(USER_PACK_NAME) {
+            match parse_pack(&text) {
+                Ok(presets) => library.add_pack(presets),
+                Err(e) => log::warn!("Ignoring user presets: {}", e),
+            }
+        }
+        library
+    }
+
+    /// Offer `presets` after the ones already loaded
+    pub fn add_pack(&mut self, presets: Vec<LocationPreset>) {
+        self.presets.extend(presets);
+    }
+
+    /// Presets for one fractal type, in the order they were loaded
+    pub fn for_type(&self, fractal_type: FractalType) -> impl Iterator<Item = &LocationPreset> {
+        self.presets
+            .iter()
+            .filter(move |preset| preset.fractal_type == fractal_type)
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+}
@@ -1,19 +1,33 @@
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
-    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferDescriptor, BufferUsages,
-    ColorTargetState, ColorWrites, CommandEncoder, Device, FragmentState, FrontFace,
-    MultisampleState, PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology,
-    Queue, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
-    RenderPipelineDescriptor, ShaderModuleDescriptor, ShaderSource, ShaderStages, TextureFormat,
-    TextureView, VertexState,
+    BindGroupLayoutEntry, BindingResource, BindingType, Buffer, BufferBindingType,
+    BufferDescriptor, BufferUsages, ColorTargetState, ColorWrites, CommandEncoder, Device,
+    Extent3d, FragmentState, FrontFace, ImageCopyTexture, ImageDataLayout, MultisampleState,
+    Origin3d, PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology, Queue,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, Texture, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+    TextureView, TextureViewDescriptor, TextureViewDimension, VertexState,
 };
 
-use crate::fractal::FractalParams;
+use std::sync::Arc;
+
+use crate::fractal::{FractalParams, FractalUniform};
+use crate::perturbation::{PerturbationParams, ReferenceOrbit, ReferenceOrbits, REFERENCE_ROWS, REFERENCE_WIDTH};
 
 const FRACTAL_SHADER: &str = include_str!("../shaders/fractal.wgsl");
 
 pub struct FractalRenderer {
     params_buffer: Buffer,
+    perturbation_buffer: Buffer,
+    reference_texture: Texture,
+    /// Reference orbits for perturbation, computed off the frame loop
+    orbits: ReferenceOrbits,
+    /// Orbit currently in `reference_texture`
+    reference: Option<Arc<ReferenceOrbit>>,
+    /// Uniform last derived from `reference` and the parameters it was derived for; the series
+    /// approximation behind it is worth redoing only when the view changes
+    reference_uniform: Option<(FractalParams, PerturbationParams)>,
     render_pipeline: RenderPipeline,
     render_bind_group: BindGroup,
 }
@@ -23,40 +37,88 @@ impl FractalRenderer {
         // Create params uniform buffer
         let params_buffer = device.create_buffer(&BufferDescriptor {
             label: Some("fractal-params-buffer"),
-            size: std::mem::size_of::<FractalParams>() as u64,
+            size: std::mem::size_of::<FractalUniform>() as u64,
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
 
+        let perturbation_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("fractal-perturbation-buffer"),
+            size: std::mem::size_of::<PerturbationParams>() as u64,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Reference orbit for perturbation, one (re, im) texel per iteration
+        let reference_texture = device.create_texture(&TextureDescriptor {
+            label: Some("fractal-reference-orbit"),
+            size: Extent3d {
+                width: REFERENCE_WIDTH,
+                height: REFERENCE_ROWS,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rg32Float,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let reference_view = reference_texture.create_view(&TextureViewDescriptor::default());
+
         // Create shader module
         let shader = device.create_shader_module(ShaderModuleDescriptor {
             label: Some("fractal-shader"),
             source: ShaderSource::Wgsl(FRACTAL_SHADER.into()),
         });
 
-        // Create bind group layout (just uniform buffer for params)
+        // Create bind group layout (params, perturbation uniform, reference orbit)
+        let uniform_entry = |binding| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
         let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("fractal-bind-group-layout"),
-            entries: &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::FRAGMENT,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
+            entries: &[
+                uniform_entry(0),
+                uniform_entry(1),
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+            ],
         });
 
         // Create bind group
         let render_bind_group = device.create_bind_group(&BindGroupDescriptor {
             label: Some("fractal-bind-group"),
             layout: &bind_group_layout,
-            entries: &[BindGroupEntry {
-                binding: 0,
-                resource: params_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: perturbation_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&reference_view),
+                },
+            ],
         });
 
         // Create pipeline layout
@@ -103,6 +165,11 @@ impl FractalRenderer {
 
         Self {
             params_buffer,
+            perturbation_buffer,
+            reference_texture,
+            orbits: ReferenceOrbits::new(),
+            reference: None,
+            reference_uniform: None,
             render_pipeline,
             render_bind_group,
         }
@@ -116,6 +183,17 @@ impl FractalRenderer {
         // No longer needed - fragment shader recalculates every frame
     }
 
+    /// Compute reference orbits during [`Self::render`] instead of off the frame loop, for
+    /// one-off renders that have no later frame to show them in
+    pub fn block_on_orbits(&mut self) {
+        self.orbits = ReferenceOrbits::blocking();
+    }
+
+    /// The reference orbits rendered against, for the automatic iteration count to share
+    pub fn orbits(&mut self) -> &mut ReferenceOrbits {
+        &mut self.orbits
+    }
+
     pub fn render(
         &mut self,
         _device: &Device,
@@ -126,7 +204,14 @@ impl FractalRenderer {
         _size: (u32, u32),
     ) {
         // Update params buffer
-        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(params));
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params.uniform()));
+
+        let perturbation = if params.perturbation() {
+            self.upload_reference(queue, params)
+        } else {
+            PerturbationParams::default()
+        };
+        queue.write_buffer(&self.perturbation_buffer, 0, bytemuck::bytes_of(&perturbation));
 
         // Render pass - fractal is calculated directly in fragment shader
         {
@@ -150,4 +235,46 @@ impl FractalRenderer {
             render_pass.draw(0..3, 0..1);
         }
     }
+
+    /// Make sure a reference orbit for `params` is in the texture: one that matches the view,
+    /// or the latest one for the same formula while a matching one is computed
+    fn upload_reference(&mut self, queue: &Queue, params: &FractalParams) -> PerturbationParams {
+        let Some(orbit) = self.orbits.request(params).or_else(|| self.orbits.latest(params)) else {
+            return PerturbationParams::default();
+        };
+        if !self.reference.as_ref().is_some_and(|r| Arc::ptr_eq(r, &orbit)) {
+            // Whole rows only; the tail of the last row is never read
+            let rows = (orbit.points.len() as u32).div_ceil(REFERENCE_WIDTH);
+            let mut texels = orbit.points.clone();
+            texels.resize((rows * REFERENCE_WIDTH) as usize, [0.0; 2]);
+            queue.write_texture(
+                ImageCopyTexture {
+                    texture: &self.reference_texture,
+                    mip_level: 0,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                bytemuck::cast_slice(&texels),
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(REFERENCE_WIDTH * 8),
+                    rows_per_image: Some(rows),
+                },
+                Extent3d {
+                    width: REFERENCE_WIDTH,
+                    height: rows,
+                    depth_or_array_layers: 1,
+                },
+            );
+            self.reference = Some(orbit.clone());
+            self.reference_uniform = None;
+        }
+
+        if let Some((_, uniform)) = self.reference_uniform.filter(|(cached, _)| cached == params) {
+            return uniform;
+        }
+        let uniform = orbit.uniform(params);
+        self.reference_uniform = Some((*params, uniform));
+        uniform
+    }
 }
@@ -0,0 +1,121 @@
+//! Versioned scene documents: the view state as JSON or RON
+//! Loading validates every field instead of trusting the raw uniform layout
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{canvas, limits, ui as ui_const};
+use crate::files;
+use crate::fractal::FractalParams;
+use crate::viewport::wrap_angle;
+
+/// Current scene document version
+pub const SCENE_VERSION: u32 = 1;
+
+/// Flag bits understood by the shader or the app
+const KNOWN_FLAGS: u32 = FractalParams::FLAG_SMOOTH
+    | FractalParams::FLAG_INVERT
+    | FractalParams::FLAG_OFFSET
+    | FractalParams::FLAG_BASINS
+    | FractalParams::FLAG_AUTO_ITERATIONS;
+
+/// A saved view, tagged with the format version it was written with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub version: u32,
+    pub params: FractalParams,
+}
+
+impl Scene {
+    pub fn new(params: &FractalParams) -> Self {
+        Self {
+            version: SCENE_VERSION,
+            params: *params,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to write scene: {e}"))
+    }
+
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("Failed to write scene: {e}"))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("Invalid scene: {e}"))
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, String> {
+        ron::from_str(text).map_err(|e| format!("Invalid scene: {e}"))
+    }
+
+    /// Parse a scene file, picking the format from the extension of `name`
+    pub fn from_file(name: &str, text: &str) -> Result<Self, String> {
+        match files::extension(name).as_deref() {
+            Some("ron") => Self::from_ron(text),
+            _ => Self::from_json(text),
+        }
+    }
+
+    /// Check the scene and return parameters that are safe to upload
+    /// Versions from the future and non-finite or non-positive values are rejected;
+    /// values outside the UI ranges are clamped and unknown flag bits are dropped.
+    /// Scenes carry no canvas size, so iterations get the deep-zoom limit when the view would be
+    /// rendered by perturbation on a canvas of the default height.
+    /// Newton roots past the last one in use are dropped
+    pub fn validate(&self) -> Result<FractalParams, String> {
+        if self.version == 0 || self.version > SCENE_VERSION {
+            return Err(format!("Unsupported scene version {}", self.version));
+        }
+
+        let p = &self.params;
+        let finite = [
+            p.power,
+            p.escape_radius,
+            p.julia_c[0],
+            p.julia_c[1],
+            p.rotation,
+            p.relaxation,
+            p.iteration_bias,
+        ];
+        let roots = &p.newton_roots[..(p.root_count as usize).min(limits::MAX_NEWTON_ROOTS)];
+        if !p.zoom.is_finite() || !finite.iter().chain(roots.iter().flatten()).all(|v| v.is_finite()) {
+            return Err("Scene contains non-finite values".to_string());
+        }
+        if p.zoom <= 0.0 {
+            return Err(format!("Zoom must be positive, got {}", p.zoom));
+        }
+
+        let max_c = limits::MAX_JULIA_C;
+        let max_root = limits::MAX_NEWTON_ROOT;
+        let roots: Vec<[f32; 2]> = roots
+            .iter()
+            .map(|root| root.map(|v| v.clamp(-max_root, max_root)))
+            .collect();
+        let mut params = FractalParams {
+            zoom: p.zoom.clamp(limits::MIN_ZOOM, limits::MAX_ZOOM),
+            max_iter: p.max_iter.max(ui_const::MIN_ITERATIONS as u32),
+            power: p.power.clamp(limits::MIN_POWER, limits::MAX_POWER),
+            escape_radius: p
+                .escape_radius
+                .clamp(limits::MIN_ESCAPE_RADIUS, limits::MAX_ESCAPE_RADIUS),
+            julia_c: [p.julia_c[0].clamp(-max_c, max_c), p.julia_c[1].clamp(-max_c, max_c)],
+            flags: p.flags & KNOWN_FLAGS,
+            rotation: wrap_angle(p.rotation as f64) as f32,
+            relaxation: p.relaxation.clamp(limits::MIN_RELAXATION, limits::MAX_RELAXATION),
+            iteration_bias: p
+                .iteration_bias
+                .clamp(limits::MIN_ITERATION_BIAS, limits::MAX_ITERATION_BIAS),
+            ..*p
+        };
+        params.set_roots(&roots);
+        let max_iter = if params.perturbation_at(canvas::DEFAULT_HEIGHT as f32) {
+            ui_const::MAX_DEEP_ITERATIONS
+        } else {
+            ui_const::MAX_ITERATIONS
+        };
+        params.max_iter = params.max_iter.min(max_iter as u32);
+        Ok(params)
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use egui::{ClippedPrimitive, Context, Slider, TexturesDelta, Ui};
 use egui_wgpu::wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
 use egui_wgpu::{Renderer, ScreenDescriptor};
@@ -5,12 +7,76 @@ use egui_winit::State;
 use winit::event::WindowEvent;
 use winit::window::Window;
 
+use crate::bookmarks::{self, Bookmarks, ThumbnailJob};
 use crate::color::ColorScheme;
-use crate::constants::{performance, ui as ui_const};
-use crate::fractal::{
-    buffalo, burning_ship, celtic, julia, mandelbrot,
-    newton, phoenix, tricorn, FractalParams, FractalType,
-};
+use crate::cpu;
+use crate::constants::{animation, export, limits, performance, ui as ui_const};
+use crate::files;
+use crate::history::History;
+use crate::keymap::{Action, Keymap};
+use crate::fractal::{FractalParams, FractalType};
+use crate::presets::{LocationPreset, PresetLibrary};
+use crate::viewport::Viewport;
+use crate::views::FractalViews;
+
+/// Requests from the control panel that the app has to carry out
+#[derive(Debug, Clone, PartialEq)]
+pub enum UiAction {
+    /// Render the current view to a PNG with embedded parameters
+    SaveImage,
+    /// Render the current view as a tiled high-resolution PNG
+    SavePoster { width: u32, height: u32 },
+    /// Write the current view as a JSON scene file
+    SaveScene,
+    /// Animate to `target` over `duration` seconds
+    FlyTo { target: Box<FractalParams>, duration: f32 },
+}
+
+/// State of the navigation controls
+struct NavigationPanel {
+    show_help: bool,
+    history: History,
+}
+
+/// State of the location presets and the user's bookmarks
+struct PresetsPanel {
+    /// Preset transition length in seconds; zero jumps straight there
+    fly_duration: f32,
+    /// Built-in presets plus any packs loaded this session
+    library: PresetLibrary,
+    /// Preset pack URL typed into the panel (web only)
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pack_url: String,
+    bookmarks: Bookmarks,
+    /// Name typed for the next bookmark
+    new_name: String,
+    /// Bookmark being renamed and its edited name
+    renaming: Option<(usize, String)>,
+    /// Thumbnails keyed by the deep-link encoding of the bookmarked view
+    thumbnails: HashMap<String, egui::TextureHandle>,
+    /// Thumbnail being rendered and its key
+    rendering: Option<(String, ThumbnailJob)>,
+    /// Last bookmark or preset pack result
+    status: String,
+}
+
+/// A change to the bookmark list picked in the panel, applied after drawing it
+enum BookmarkEdit {
+    Rename(usize, String),
+    Remove(usize),
+    Move(usize, usize),
+}
+
+/// State of the image save/load controls
+struct ImagePanel {
+    /// Path typed into the "Load from PNG" field (native only)
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    load_path: String,
+    /// Last save/load result shown under the buttons
+    status: String,
+    /// Output size for poster export
+    poster_size: [u32; 2],
+}
 
 pub struct UiState {
     ctx: Context,
@@ -18,8 +84,15 @@ pub struct UiState {
     renderer: Renderer,
     pending_frame: Option<PreparedFrame>,
     panel_width: f32,
-    /// Color scheme per fractal type (indexed by FractalType as u32)
-    fractal_colors: [u32; 9],
+    /// Last view of every fractal type
+    views: FractalViews,
+    /// Actions requested this frame, drained by the app
+    actions: Vec<UiAction>,
+    image: ImagePanel,
+    /// Box zoom rectangle to outline, in points
+    selection: Option<egui::Rect>,
+    navigation: NavigationPanel,
+    presets: PresetsPanel,
 }
 
 struct PreparedFrame {
@@ -59,20 +132,121 @@ impl UiState {
             renderer,
             pending_frame: None,
             panel_width: ui_const::PANEL_WIDTH,
-            fractal_colors: [
-                FractalType::Mandelbrot.default_color_scheme(),
-                FractalType::Julia.default_color_scheme(),
-                FractalType::BurningShip.default_color_scheme(),
-                FractalType::Tricorn.default_color_scheme(),
-                FractalType::Celtic.default_color_scheme(),
-                FractalType::BuffaloJulia.default_color_scheme(),
-                FractalType::CelticJulia.default_color_scheme(),
-                FractalType::Newton.default_color_scheme(),
-                FractalType::Phoenix.default_color_scheme(),
-            ],
+            views: FractalViews::load(),
+            actions: Vec::new(),
+            image: ImagePanel {
+                load_path: String::new(),
+                status: String::new(),
+                poster_size: [export::DEFAULT_POSTER_SIZE; 2],
+            },
+            selection: None,
+            navigation: NavigationPanel {
+                show_help: false,
+                history: History::new(),
+            },
+            presets: PresetsPanel {
+                fly_duration: animation::FLY_DURATION,
+                library: PresetLibrary::load(),
+                pack_url: String::new(),
+                bookmarks: Bookmarks::load(),
+                new_name: String::new(),
+                renaming: None,
+                thumbnails: HashMap::new(),
+                rendering: None,
+                status: String::new(),
+            },
+        }
+    }
+
+    /// Take the actions requested since the last call
+    pub fn take_actions(&mut self) -> Vec<UiAction> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// Show a save/load result in the panel
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.image.status = status.into();
+    }
+
+    /// Add bookmarks read from an exported file to the end of the list
+    pub fn import_bookmarks(&mut self, imported: Bookmarks, name: &str) {
+        let count = imported.len();
+        let presets = &mut self.presets;
+        presets.bookmarks.append(imported);
+        presets.status = match presets.bookmarks.store() {
+            Ok(()) => format!("Imported {count} bookmarks from {name}"),
+            Err(e) => e,
+        };
+    }
+
+    /// Offer the presets of a loaded preset pack after the existing ones
+    pub fn add_preset_pack(&mut self, presets: Vec<LocationPreset>, name: &str) {
+        self.presets.status = format!("Added {} presets from {name}", presets.len());
+        self.presets.library.add_pack(presets);
+    }
+
+    /// Switch to the fractal type after the current one (wrapping around)
+    pub fn next_fractal_type(&mut self, params: &mut FractalParams) {
+        let all = FractalType::all();
+        let current = all.iter().position(|ft| *ft == params.get_fractal_type()).unwrap_or(0);
+        self.views.switch(params, all[(current + 1) % all.len()]);
+    }
+
+    /// Switch to the color scheme after the current one (wrapping around)
+    pub fn next_color_scheme(&mut self, params: &mut FractalParams) {
+        let all = ColorScheme::all();
+        let current = ColorScheme::from_u32(params.color_scheme);
+        let index = all.iter().position(|cs| *cs == current).unwrap_or(0);
+        params.color_scheme = all[(index + 1) % all.len()] as u32;
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.navigation.show_help = !self.navigation.show_help;
+    }
+
+    /// Step back in the view history; returns true if `params` changed
+    pub fn undo(&mut self, params: &mut FractalParams) -> bool {
+        self.navigation.history.undo(params)
+    }
+
+    /// Step forward in the view history; returns true if `params` changed
+    pub fn redo(&mut self, params: &mut FractalParams) -> bool {
+        self.navigation.history.redo(params)
+    }
+
+    /// Feed the current parameters to the undo history and the per-fractal memory at time `now`
+    /// `busy` holds off recording while the app is still moving the view;
+    /// a pointer held down on the panel (e.g. scrubbing a slider) counts as busy too.
+    /// The memory is written to storage whenever the history settles on a new entry
+    pub fn record_history(&mut self, params: &FractalParams, busy: bool, now: f64) {
+        let busy = busy || self.ctx.input(|i| i.pointer.any_down());
+        let recorded = self.navigation.history.observe(params, busy, now);
+        self.views.remember(params);
+        if recorded {
+            self.store_views();
+        }
+    }
+
+    /// The view that was shown when the last session ended
+    pub fn restored_view(&self) -> FractalParams {
+        self.views.current()
+    }
+
+    /// Write the per-fractal memory to storage
+    pub fn store_views(&self) {
+        if let Err(e) = self.views.store() {
+            log::warn!("{}", e);
         }
     }
 
+    /// Outline a box zoom between two corners given in physical pixels, or clear it
+    pub fn set_selection(&mut self, corners: Option<((f32, f32), (f32, f32))>) {
+        let ppp = self.ctx.pixels_per_point();
+        self.selection = corners.map(|((x0, y0), (x1, y1))| {
+            egui::Rect::from_two_pos(egui::pos2(x0 / ppp, y0 / ppp), egui::pos2(x1 / ppp, y1 / ppp))
+        });
+    }
+
     #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
     pub fn get_panel_width(&self) -> f32 {
         self.panel_width
@@ -84,7 +258,7 @@ impl UiState {
     }
 
     /// Run egui for this frame and stage paint jobs. Returns true if params changed.
-    pub fn prepare(&mut self, window: &Window, params: &mut FractalParams) -> bool {
+    pub fn prepare(&mut self, window: &Window, params: &mut FractalParams, keymap: &Keymap) -> bool {
         let size = window.inner_size();
         let screen_descriptor = ScreenDescriptor {
             size_in_pixels: [size.width.max(1), size.height.max(1)],
@@ -94,10 +268,22 @@ impl UiState {
         let raw_input = self.state.take_egui_input(window);
         let params_before = *params;
         let mut panel_width = self.panel_width;
-        let fractal_colors = &mut self.fractal_colors;
+        let views = &mut self.views;
+        let actions = &mut self.actions;
+        let image = &mut self.image;
+        let selection = self.selection;
+        let navigation = &mut self.navigation;
+        let presets = &mut self.presets;
 
         let full_output = self.ctx.run(raw_input, |ctx| {
-            panel_width = Self::build_ui(ctx, params, fractal_colors);
+            panel_width = Self::build_ui(ctx, params, views, actions, image, navigation, presets);
+            if params.get_fractal_type() == FractalType::Newton && params.root_count > 0 {
+                Self::root_handles(ctx, params, panel_width);
+            }
+            if let Some(rect) = selection {
+                Self::selection_overlay(ctx, rect);
+            }
+            Self::help_window(ctx, keymap, &mut navigation.show_help);
         });
         self.panel_width = panel_width;
 
@@ -117,7 +303,7 @@ impl UiState {
         params_before != *params
     }
 
-    fn fractal_type_section(ui: &mut Ui, params: &mut FractalParams, fractal_colors: &mut [u32; 9]) {
+    fn fractal_type_section(ui: &mut Ui, params: &mut FractalParams, views: &mut FractalViews) {
         ui.heading("Fractal Type");
 
         let current = params.get_fractal_type();
@@ -127,19 +313,7 @@ impl UiState {
                 for ft in FractalType::all() {
                     let selected = *ft == current;
                     if ui.selectable_label(selected, ft.name()).clicked() {
-                        // Save current color for current fractal
-                        let old_type = params.fractal_type as usize;
-                        if let Some(color) = fractal_colors.get_mut(old_type) {
-                            *color = params.color_scheme;
-                        }
-
-                        // Switch fractal type
-                        params.set_fractal_type(*ft);
-
-                        // Load saved color for new fractal
-                        params.color_scheme = fractal_colors.get(*ft as usize).copied().unwrap_or(0);
-
-                        params.reset();
+                        views.switch(params, *ft);
                     }
                 }
             });
@@ -148,14 +322,30 @@ impl UiState {
     fn parameters_section(ui: &mut Ui, params: &mut FractalParams) {
         ui.label("Parameters");
 
-        // Iterations (increased to 10000)
+        // Iterations, up to far more in perturbation views; auto mode picks them from the view instead
+        let auto = params.auto_iterations();
+        let max_iterations = params.max_iterations();
         let mut max_iter = params.max_iter as f32;
-        ui.add(
-            Slider::new(&mut max_iter, ui_const::MIN_ITERATIONS..=ui_const::MAX_ITERATIONS)
+        ui.add_enabled(
+            !auto,
+            Slider::new(&mut max_iter, ui_const::MIN_ITERATIONS..=max_iterations as f32)
                 .logarithmic(true)
                 .text("Iterations"),
         );
-        params.max_iter = max_iter as u32;
+        // Leaving a perturbation view brings the count back within the lower limit
+        params.max_iter = (max_iter as u32).min(max_iterations);
+
+        let mut auto_enabled = auto;
+        ui.checkbox(&mut auto_enabled, "Auto iterations")
+            .on_hover_text("Derive iterations from the zoom and how many boundary pixels reach the limit");
+        params.set_auto_iterations(auto_enabled);
+        if auto_enabled {
+            ui.add(
+                Slider::new(&mut params.iteration_bias, limits::MIN_ITERATION_BIAS..=limits::MAX_ITERATION_BIAS)
+                    .logarithmic(true)
+                    .text("Bias"),
+            );
+        }
 
         // Fractal-specific performance warning thresholds
         let warning_threshold = match params.get_fractal_type() {
@@ -167,20 +357,28 @@ impl UiState {
             _ => performance::BASELINE_WARNING_THRESHOLD,
         };
 
-        if params.max_iter > warning_threshold {
-            let (r, g, b) = crate::constants::ui::WARNING_COLOR;
-            ui.colored_label(
-                egui::Color32::from_rgb(r, g, b),
-                "High iterations may reduce performance",
-            );
+        let warn = params.max_iter > warning_threshold;
+        if auto_enabled || warn {
+            ui.horizontal_wrapped(|ui| {
+                if auto_enabled {
+                    ui.label(format!("Auto: {}", params.max_iter));
+                }
+                if warn {
+                    let (r, g, b) = crate::constants::ui::WARNING_COLOR;
+                    ui.colored_label(
+                        egui::Color32::from_rgb(r, g, b),
+                        "High iterations may reduce performance",
+                    );
+                }
+            });
         }
 
         // Power
-        ui.add(Slider::new(&mut params.power, 2.0..=8.0).text("Power"));
+        ui.add(Slider::new(&mut params.power, limits::MIN_POWER..=limits::MAX_POWER).text("Power"));
 
         // Escape radius
         ui.add(
-            Slider::new(&mut params.escape_radius, 2.0..=100.0)
+            Slider::new(&mut params.escape_radius, limits::MIN_ESCAPE_RADIUS..=limits::MAX_ESCAPE_RADIUS)
                 .logarithmic(true)
                 .text("Escape Radius"),
         );
@@ -196,11 +394,67 @@ impl UiState {
         ui.label(label);
 
         // Parameter sliders
-        ui.add(Slider::new(&mut params.julia_c[0], -2.0..=2.0).text("Real"));
-        ui.add(Slider::new(&mut params.julia_c[1], -2.0..=2.0).text("Imaginary"));
+        ui.add(Slider::new(&mut params.julia_c[0], -limits::MAX_JULIA_C..=limits::MAX_JULIA_C).text("Real"));
+        ui.add(Slider::new(&mut params.julia_c[1], -limits::MAX_JULIA_C..=limits::MAX_JULIA_C).text("Imaginary"));
+    }
+
+    fn newton_section(ui: &mut Ui, params: &mut FractalParams) {
+        ui.label("Newton Polynomial");
+
+        ui.add(
+            Slider::new(&mut params.relaxation, limits::MIN_RELAXATION..=limits::MAX_RELAXATION)
+                .text("Relaxation (a)"),
+        );
+
+        if params.roots().is_empty() {
+            ui.small("Roots of z^n - 1, set by Power");
+            if ui.button("Edit Roots").clicked() {
+                // Start from the picture on screen: the roots of unity for the rounded power
+                let roots: Vec<[f32; 2]> = cpu::iterate::newton_roots(params.power.round())
+                    .iter()
+                    .map(|(root, _)| [root.x, root.y])
+                    .collect();
+                params.set_roots(&roots);
+            }
+            return;
+        }
+
+        let max_root = limits::MAX_NEWTON_ROOT;
+        let mut roots = params.roots().to_vec();
+        let mut remove = None;
+        for (i, root) in roots.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}", i + 1));
+                ui.add(egui::DragValue::new(&mut root[0]).range(-max_root..=max_root).speed(0.01).prefix("re "));
+                ui.add(egui::DragValue::new(&mut root[1]).range(-max_root..=max_root).speed(0.01).prefix("im "));
+                if ui.small_button("Remove").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove {
+            roots.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(roots.len() < limits::MAX_NEWTON_ROOTS, egui::Button::new("Add Root"))
+                .clicked()
+            {
+                roots.push(params.center_f64().map(|v| (v as f32).clamp(-max_root, max_root)));
+            }
+            if ui.button("Use z^n - 1").clicked() {
+                roots.clear();
+            }
+        });
+        ui.small("Drag the markers on the canvas to move roots");
+
+        if roots != params.roots() {
+            params.set_roots(&roots);
+        }
     }
 
-    fn color_section(ui: &mut Ui, params: &mut FractalParams, fractal_colors: &mut [u32; 9]) {
+    fn color_section(ui: &mut Ui, params: &mut FractalParams) {
         ui.label("Color Scheme");
 
         let current = ColorScheme::from_u32(params.color_scheme);
@@ -211,10 +465,6 @@ impl UiState {
                     let selected = *cs == current;
                     if ui.selectable_label(selected, cs.name()).clicked() {
                         params.color_scheme = *cs as u32;
-                        // Save color for current fractal type
-                        if let Some(color) = fractal_colors.get_mut(params.fractal_type as usize) {
-                            *color = params.color_scheme;
-                        }
                     }
                 }
             });
@@ -235,54 +485,317 @@ impl UiState {
                 params.set_offset(offset);
             }
         });
+
+        if params.get_fractal_type() == FractalType::Newton {
+            let mut basins = params.basins();
+            if ui
+                .checkbox(&mut basins, "Basins")
+                .on_hover_text("Color each point by the root it converges to")
+                .changed()
+            {
+                params.set_basins(basins);
+            }
+        }
     }
 
-    fn navigation_section(ui: &mut Ui, params: &mut FractalParams) {
+    fn navigation_section(ui: &mut Ui, params: &mut FractalParams, navigation: &mut NavigationPanel) {
         ui.label("Navigation");
 
         // Zoom slider (logarithmic)
+        // Only write back on change: the log10/powf round trip is not exact
         let mut log_zoom = params.zoom.log10();
-        ui.add(Slider::new(&mut log_zoom, -10.0..=10.0).text("Zoom (log)"));
-        params.zoom = 10.0_f32.powf(log_zoom);
+        let zoom_range = limits::MIN_ZOOM.log10()..=limits::MAX_ZOOM.log10();
+        if ui.add(Slider::new(&mut log_zoom, zoom_range).text("Zoom (log)")).changed() {
+            params.zoom = 10.0_f64.powf(log_zoom);
+        }
+
+        let mut degrees = params.rotation.to_degrees();
+        if ui
+            .add(Slider::new(&mut degrees, -180.0..=180.0).text("Rotation (°)").fixed_decimals(1))
+            .changed()
+        {
+            params.rotation = degrees.to_radians();
+        }
+
+        // Center coordinates (display only), with enough digits to tell neighbouring pixels apart
+        let pixel_size = 2.0 / (params.resolution[1] as f64 * params.zoom);
+        let digits = (-pixel_size.log10()).ceil().max(5.0) as usize + 1;
+        ui.label(format!(
+            "Center: ({}, {})",
+            params.center[0].to_decimal(digits),
+            params.center[1].to_decimal(digits)
+        ));
+        if params.perturbation() {
+            ui.small("Perturbation rendering (CPU reference orbit)");
+        } else if params.high_precision() {
+            ui.small("High precision (double-single) rendering");
+        }
 
-        // Center coordinates (display only)
         ui.horizontal(|ui| {
-            ui.label(format!("Center: ({:.6}, {:.6})", params.center[0], params.center[1]));
+            // Reset button
+            if ui.button("Reset View").clicked() {
+                params.reset();
+            }
+            if ui.button("Shortcuts").clicked() {
+                navigation.show_help = !navigation.show_help;
+            }
         });
 
-        // Reset button
-        if ui.button("Reset View").clicked() {
-            params.reset();
-        }
+        ui.horizontal(|ui| {
+            let history = &mut navigation.history;
+            if ui.add_enabled(history.can_undo(), egui::Button::new("Undo")).clicked() {
+                history.undo(params);
+            }
+            if ui.add_enabled(history.can_redo(), egui::Button::new("Redo")).clicked() {
+                history.redo(params);
+            }
+        });
     }
 
-    fn presets_section(ui: &mut Ui, params: &mut FractalParams) {
+    fn presets_section(ui: &mut Ui, params: &FractalParams, actions: &mut Vec<UiAction>, presets: &mut PresetsPanel) {
         ui.label("Location Presets");
 
-        let presets = match params.get_fractal_type() {
-            FractalType::Mandelbrot => mandelbrot::presets(),
-            FractalType::Julia => julia::presets(),
-            FractalType::BurningShip => burning_ship::presets(),
-            FractalType::Tricorn => tricorn::presets(),
-            FractalType::Celtic => celtic::presets(),
-            FractalType::BuffaloJulia => buffalo::julia_presets(),
-            FractalType::CelticJulia => celtic::julia_presets(),
-            FractalType::Newton => newton::presets(),
-            FractalType::Phoenix => phoenix::presets(),
-        };
+        ui.add(
+            Slider::new(&mut presets.fly_duration, 0.0..=animation::MAX_FLY_DURATION)
+                .text("Transition (s)")
+                .fixed_decimals(1),
+        );
 
         egui::Grid::new("location_presets_grid")
             .num_columns(2)
             .show(ui, |ui| {
-                for (i, preset) in presets.iter().enumerate() {
-                    if ui.button(preset.name).clicked() {
-                        preset.apply(params);
+                for (i, preset) in presets.library.for_type(params.get_fractal_type()).enumerate() {
+                    if ui.button(&preset.name).clicked() {
+                        let mut target = *params;
+                        preset.apply(&mut target);
+                        actions.push(UiAction::FlyTo { target: Box::new(target), duration: presets.fly_duration });
                     }
                     if (i + 1) % 2 == 0 {
                         ui.end_row();
                     }
                 }
         });
+
+        // Extra presets come from preset pack files in the same format as assets/presets.json
+        #[cfg(target_arch = "wasm32")]
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut presets.pack_url)
+                    .hint_text("Preset pack URL")
+                    .desired_width(120.0),
+            );
+            let url = presets.pack_url.trim();
+            if ui.button("Fetch").clicked() && !url.is_empty() {
+                files::fetch_url(url);
+            }
+            if ui.button("Open").clicked() {
+                files::pick_file(".json");
+            }
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.small("Drop a preset pack (.json) onto the window to add presets");
+    }
+
+    fn bookmarks_section(ui: &mut Ui, params: &FractalParams, actions: &mut Vec<UiAction>, presets: &mut PresetsPanel) {
+        ui.label("Bookmarks");
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut presets.new_name)
+                    .hint_text("Bookmark name")
+                    .desired_width(150.0),
+            );
+            let name = presets.new_name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("Save View")).clicked() {
+                presets.bookmarks.add(name, params);
+                presets.new_name.clear();
+                changed = true;
+            }
+        });
+
+        let [thumb_width, thumb_height] = bookmarks::THUMBNAIL_SIZE;
+        let thumb_size = egui::vec2(thumb_width as f32, thumb_height as f32);
+
+        // Thumbnails are rendered one at a time, off the frame loop where threads exist
+        if let Some((key, job)) = &mut presets.rendering {
+            match job.poll() {
+                Some(pixels) => {
+                    let image = egui::ColorImage::from_rgba_unmultiplied(
+                        [thumb_width as usize, thumb_height as usize],
+                        &pixels,
+                    );
+                    let texture = ui.ctx().load_texture(key.as_str(), image, egui::TextureOptions::LINEAR);
+                    presets.thumbnails.insert(key.clone(), texture);
+                    presets.rendering = None;
+                }
+                None => ui.ctx().request_repaint(),
+            }
+        }
+
+        // Drop the thumbnails of deleted bookmarks
+        let bookmarks = &presets.bookmarks;
+        presets.thumbnails.retain(|key, _| bookmarks.iter().any(|bookmark| bookmark.key() == key));
+
+        let mut edit = None;
+        let count = presets.bookmarks.len();
+
+        for (i, bookmark) in presets.bookmarks.iter().enumerate() {
+            let key = bookmark.key();
+            if presets.rendering.is_none() && !presets.thumbnails.contains_key(key) {
+                presets.rendering = Some((key.to_string(), ThumbnailJob::start(&bookmark.params)));
+                ui.ctx().request_repaint();
+            }
+
+            ui.horizontal(|ui| {
+                match presets.thumbnails.get(key) {
+                    Some(texture) => {
+                        ui.image((texture.id(), thumb_size));
+                    }
+                    None => {
+                        ui.allocate_space(thumb_size);
+                    }
+                }
+
+                ui.vertical(|ui| {
+                    match &mut presets.renaming {
+                        Some((index, name)) if *index == i => {
+                            ui.horizontal(|ui| {
+                                let response = ui.add(egui::TextEdit::singleline(name).desired_width(110.0));
+                                let entered = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                                if (ui.small_button("OK").clicked() || entered) && !name.trim().is_empty() {
+                                    edit = Some(BookmarkEdit::Rename(i, name.clone()));
+                                }
+                            });
+                        }
+                        _ => {
+                            if ui.button(&bookmark.name).clicked() {
+                                // Views of another fractal type cannot be blended, so jump there
+                                let duration = if bookmark.params.fractal_type == params.fractal_type {
+                                    presets.fly_duration
+                                } else {
+                                    0.0
+                                };
+                                let target = FractalParams {
+                                    resolution: params.resolution,
+                                    ui_offset: params.ui_offset,
+                                    ui_offset_y: params.ui_offset_y,
+                                    ..bookmark.params
+                                };
+                                actions.push(UiAction::FlyTo { target: Box::new(target), duration });
+                            }
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(i > 0, egui::Button::new("Up").small()).clicked() {
+                            edit = Some(BookmarkEdit::Move(i, i - 1));
+                        }
+                        if ui.add_enabled(i + 1 < count, egui::Button::new("Down").small()).clicked() {
+                            edit = Some(BookmarkEdit::Move(i, i + 1));
+                        }
+                        if ui.small_button("Rename").clicked() {
+                            presets.renaming = Some((i, bookmark.name.clone()));
+                        }
+                        if ui.small_button("Delete").clicked() {
+                            edit = Some(BookmarkEdit::Remove(i));
+                        }
+                    });
+                });
+            });
+        }
+
+        if let Some(edit) = edit {
+            match edit {
+                BookmarkEdit::Rename(i, name) => presets.bookmarks.rename(i, &name),
+                BookmarkEdit::Remove(i) => {
+                    presets.bookmarks.remove(i);
+                }
+                BookmarkEdit::Move(from, to) => presets.bookmarks.move_to(from, to),
+            }
+            presets.renaming = None;
+            changed = true;
+        }
+
+        if changed {
+            presets.status = match presets.bookmarks.store() {
+                Ok(()) => String::new(),
+                Err(e) => e,
+            };
+        }
+
+        if ui
+            .add_enabled(!presets.bookmarks.is_empty(), egui::Button::new("Export Bookmarks"))
+            .clicked()
+        {
+            let file_name = format!("fractal-madness-bookmarks-{}.json", files::timestamp());
+            presets.status = match presets
+                .bookmarks
+                .to_json()
+                .and_then(|json| files::save_bytes(&file_name, json.as_bytes(), "application/json"))
+            {
+                Ok(path) => format!("Saved {path}"),
+                Err(e) => e,
+            };
+        }
+
+        if !presets.status.is_empty() {
+            ui.small(&presets.status);
+        }
+    }
+
+    fn image_section(ui: &mut Ui, actions: &mut Vec<UiAction>, image: &mut ImagePanel) {
+        ui.label("Image");
+
+        ui.horizontal(|ui| {
+            if ui.button("Save Image").clicked() {
+                actions.push(UiAction::SaveImage);
+            }
+            if ui.button("Save Scene").clicked() {
+                actions.push(UiAction::SaveScene);
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            if ui.button("Load View").clicked() {
+                files::pick_file(".png,.json,.ron");
+            }
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut image.load_path)
+                        .hint_text("image.png / scene.json")
+                        .desired_width(150.0),
+                );
+                let path = image.load_path.trim();
+                if ui.button("Load View").clicked() && !path.is_empty() {
+                    files::open_path(std::path::Path::new(path));
+                }
+            });
+            ui.small("Or drop a PNG or scene file onto the window");
+        }
+
+        // Poster export renders in tiles, so it is not bound by the texture size limit
+        ui.horizontal(|ui| {
+            let [width, height] = &mut image.poster_size;
+            let range = export::MAX_TILE_SIZE / 8..=export::MAX_POSTER_SIZE;
+            ui.add(egui::DragValue::new(width).range(range.clone()).speed(64));
+            ui.label("x");
+            ui.add(egui::DragValue::new(height).range(range).speed(64));
+            if ui.button("Save Poster").clicked() {
+                actions.push(UiAction::SavePoster {
+                    width: *width,
+                    height: *height,
+                });
+            }
+        });
+
+        if !image.status.is_empty() {
+            ui.small(&image.status);
+        }
     }
 
     /// Paint the previously prepared egui frame over the target view.
@@ -335,8 +848,109 @@ impl UiState {
         }
     }
 
+    /// List the keyboard shortcuts from `keymap` and the mouse controls
+    fn help_window(ctx: &Context, keymap: &Keymap, show_help: &mut bool) {
+        egui::Window::new("Shortcuts")
+            .open(show_help)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_TOP, [-8.0, 8.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for action in Action::all() {
+                            let keys: Vec<&str> = keymap.keys_for(*action).collect();
+                            if keys.is_empty() {
+                                continue;
+                            }
+                            ui.monospace(keys.join(" / "));
+                            ui.label(action.description());
+                            ui.end_row();
+                        }
+
+                        for (input, description) in [
+                            ("Drag", "Pan"),
+                            ("Wheel", "Zoom toward cursor"),
+                            ("Right Drag", "Rotate"),
+                            ("Shift+Drag", "Box zoom in"),
+                            ("Shift+Right Drag", "Box zoom out"),
+                        ] {
+                            ui.monospace(input);
+                            ui.label(description);
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    /// Draggable markers over the custom Newton roots
+    /// Markers that would sit on the control panel (right edge at `panel_width` points) are hidden
+    fn root_handles(ctx: &Context, params: &mut FractalParams, panel_width: f32) {
+        let viewport = Viewport::from_params(params);
+        let ppp = ctx.pixels_per_point();
+        let radius = ui_const::ROOT_HANDLE_RADIUS;
+        let max_root = limits::MAX_NEWTON_ROOT as f64;
+        let mut roots = params.roots().to_vec();
+
+        for (i, root) in roots.iter_mut().enumerate() {
+            let [x, y] = viewport.complex_to_screen([root[0] as f64, root[1] as f64]);
+            let center = egui::pos2(x as f32 / ppp, y as f32 / ppp);
+            if center.x < panel_width + radius || !ctx.screen_rect().contains(center) {
+                continue;
+            }
+
+            egui::Area::new(egui::Id::new(("newton_root", i)))
+                .order(egui::Order::Foreground)
+                .fixed_pos(center - egui::vec2(radius, radius))
+                .show(ctx, |ui| {
+                    let size = egui::vec2(2.0 * radius, 2.0 * radius);
+                    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::drag());
+                    let fill = if response.hovered() || response.dragged() {
+                        egui::Color32::WHITE
+                    } else {
+                        egui::Color32::from_white_alpha(96)
+                    };
+                    let painter = ui.painter();
+                    painter.circle_filled(rect.center(), radius - 1.0, fill);
+                    painter.circle_stroke(rect.center(), radius - 1.0, egui::Stroke::new(2.0_f32, egui::Color32::BLACK));
+
+                    if response.dragged() {
+                        if let Some(pos) = response.interact_pointer_pos() {
+                            let c = viewport.screen_to_complex([(pos.x * ppp) as f64, (pos.y * ppp) as f64]);
+                            *root = c.map(|v| v.clamp(-max_root, max_root) as f32);
+                        }
+                    }
+                    response.on_hover_text(format!("Root {}", i + 1));
+                });
+        }
+
+        if roots != params.roots() {
+            params.set_roots(&roots);
+        }
+    }
+
+    /// Draw the box zoom rectangle above the fractal
+    fn selection_overlay(ctx: &Context, rect: egui::Rect) {
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Foreground,
+            egui::Id::new("box_zoom"),
+        ));
+        painter.rect_filled(rect, egui::Rounding::ZERO, egui::Color32::from_white_alpha(24));
+        painter.rect_stroke(rect, egui::Rounding::ZERO, egui::Stroke::new(1.0_f32, egui::Color32::WHITE));
+    }
+
     /// Build egui widgets and return the panel width.
-    fn build_ui(ctx: &Context, params: &mut FractalParams, fractal_colors: &mut [u32; 9]) -> f32 {
+    fn build_ui(
+        ctx: &Context,
+        params: &mut FractalParams,
+        views: &mut FractalViews,
+        actions: &mut Vec<UiAction>,
+        image: &mut ImagePanel,
+        navigation: &mut NavigationPanel,
+        presets: &mut PresetsPanel,
+    ) -> f32 {
         let response = egui::SidePanel::left("controls")
             .resizable(true)
             .default_width(ui_const::PANEL_WIDTH)
@@ -344,7 +958,7 @@ impl UiState {
             .show(ctx, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
 
-                    Self::fractal_type_section(ui, params, fractal_colors);
+                    Self::fractal_type_section(ui, params, views);
                     ui.separator();
 
                     Self::parameters_section(ui, params);
@@ -356,13 +970,24 @@ impl UiState {
                         ui.separator();
                     }
 
-                    Self::color_section(ui, params, fractal_colors);
+                    if params.get_fractal_type() == FractalType::Newton {
+                        Self::newton_section(ui, params);
+                        ui.separator();
+                    }
+
+                    Self::color_section(ui, params);
+                    ui.separator();
+
+                    Self::navigation_section(ui, params, navigation);
+                    ui.separator();
+
+                    Self::presets_section(ui, params, actions, presets);
                     ui.separator();
 
-                    Self::navigation_section(ui, params);
+                    Self::bookmarks_section(ui, params, actions, presets);
                     ui.separator();
 
-                    Self::presets_section(ui, params);
+                    Self::image_section(ui, actions, image);
                 });
             });
         response.response.rect.width()
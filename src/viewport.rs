@@ -0,0 +1,197 @@
+//! The mapping between screen pixels and the complex plane
+//! Mirrors `fs_main`: NDC = (uv - 0.5) * 2 scaled by aspect, shifted by the panel offset,
+//! rotated about the visible center, then divided by zoom and added to the center
+//! Positions are worked out as offsets from the exact center, so moving the view keeps
+//! every bit of it however deep the zoom
+
+use crate::constants::limits;
+use crate::fractal::{Coordinate, FractalParams};
+
+/// View transform: an exact center, everything else in double precision
+/// Screen positions are continuous physical-pixel coordinates with y pointing down;
+/// the center of pixel (i, j) is at (i + 0.5, j + 0.5)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// Complex coordinate shown at the center of the visible area
+    pub center: [Coordinate; 2],
+    pub zoom: f64,
+    /// View rotation in radians, in (-pi, pi]; positive angles turn the image
+    /// counter-clockwise on screen
+    pub rotation: f64,
+    /// Width and height in physical pixels
+    pub resolution: [f64; 2],
+    /// Shift of the visible center in NDC units (`ui_offset`, `ui_offset_y`)
+    pub offset: [f64; 2],
+}
+
+impl Viewport {
+    pub fn from_params(params: &FractalParams) -> Self {
+        Self {
+            center: params.center,
+            zoom: params.zoom,
+            rotation: params.rotation as f64,
+            resolution: [params.resolution[0] as f64, params.resolution[1] as f64],
+            offset: [params.ui_offset as f64, params.ui_offset_y as f64],
+        }
+    }
+
+    /// The view of `params` as it appears on a surface of `size` physical pixels
+    pub fn for_screen(params: &FractalParams, size: (u32, u32)) -> Self {
+        Self {
+            resolution: [size.0 as f64, size.1 as f64],
+            ..Self::from_params(params)
+        }
+    }
+
+    /// Write the view back into the shader parameters
+    pub fn apply(&self, params: &mut FractalParams) {
+        params.center = self.center;
+        params.zoom = self.zoom;
+        params.rotation = self.rotation as f32;
+        params.resolution = [self.resolution[0] as f32, self.resolution[1] as f32];
+        params.ui_offset = self.offset[0] as f32;
+        params.ui_offset_y = self.offset[1] as f32;
+    }
+
+    /// Center the view in the area right of a side panel `panel_width` physical pixels wide
+    pub fn set_panel_width(&mut self, panel_width: f64) {
+        self.offset = [-panel_width / self.resolution[0] * self.aspect(), 0.0];
+    }
+
+    pub fn aspect(&self) -> f64 {
+        self.resolution[0] / self.resolution[1]
+    }
+
+    /// Width and height of the area not covered by the panel, in pixels
+    pub fn visible_size(&self) -> [f64; 2] {
+        // An NDC offset of `o` hides `o * height` pixels (see `set_panel_width`)
+        [
+            self.resolution[0] - (self.offset[0] * self.resolution[1]).abs(),
+            self.resolution[1] - (self.offset[1] * self.resolution[1]).abs(),
+        ]
+    }
+
+    /// Size of one pixel in the complex plane (the same in both axes)
+    pub fn pixel_size(&self) -> f64 {
+        2.0 / (self.resolution[1] * self.zoom)
+    }
+
+    /// Rotate a screen-aligned vector into the complex plane
+    fn rotate(&self, v: [f64; 2], angle: f64) -> [f64; 2] {
+        let (sin, cos) = angle.sin_cos();
+        [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos]
+    }
+
+    /// Complex offset from the center of the point under `screen`
+    pub fn screen_to_offset(&self, screen: [f64; 2]) -> [f64; 2] {
+        let ndc = [
+            (screen[0] / self.resolution[0] - 0.5) * 2.0 * self.aspect() + self.offset[0],
+            (screen[1] / self.resolution[1] - 0.5) * 2.0 + self.offset[1],
+        ];
+        let d = self.rotate(ndc, self.rotation);
+        [d[0] / self.zoom, d[1] / self.zoom]
+    }
+
+    /// Screen position of the point at complex offset `d` from the center
+    pub fn offset_to_screen(&self, d: [f64; 2]) -> [f64; 2] {
+        let ndc = self.rotate([d[0] * self.zoom, d[1] * self.zoom], -self.rotation);
+        [
+            ((ndc[0] - self.offset[0]) / (2.0 * self.aspect()) + 0.5) * self.resolution[0],
+            ((ndc[1] - self.offset[1]) / 2.0 + 0.5) * self.resolution[1],
+        ]
+    }
+
+    /// The complex point under `screen`, exactly
+    pub fn screen_to_coordinate(&self, screen: [f64; 2]) -> [Coordinate; 2] {
+        let d = self.screen_to_offset(screen);
+        [self.center[0] + Coordinate::from_f64(d[0]), self.center[1] + Coordinate::from_f64(d[1])]
+    }
+
+    /// The complex point under `screen`, rounded to f64
+    pub fn screen_to_complex(&self, screen: [f64; 2]) -> [f64; 2] {
+        self.screen_to_coordinate(screen).map(|c| c.to_f64())
+    }
+
+    /// Screen position of the exact complex point `c`
+    pub fn coordinate_to_screen(&self, c: [Coordinate; 2]) -> [f64; 2] {
+        self.offset_to_screen([(c[0] - self.center[0]).to_f64(), (c[1] - self.center[1]).to_f64()])
+    }
+
+    pub fn complex_to_screen(&self, c: [f64; 2]) -> [f64; 2] {
+        let center = self.center.map(|c| c.to_f64());
+        self.offset_to_screen([c[0] - center[0], c[1] - center[1]])
+    }
+
+    /// Move the center by the complex offset `d`
+    fn shift(&mut self, d: [f64; 2]) {
+        self.center = [self.center[0] + Coordinate::from_f64(d[0]), self.center[1] + Coordinate::from_f64(d[1])];
+    }
+
+    /// Move the view so content follows a drag of (`dx`, `dy`) pixels exactly
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        let d = self.rotate([dx, dy], self.rotation);
+        let pixel = self.pixel_size();
+        self.shift([-d[0] * pixel, -d[1] * pixel]);
+    }
+
+    /// Turn the image counter-clockwise by `angle` radians about the visible center
+    pub fn rotate_by(&mut self, angle: f64) {
+        self.rotation = wrap_angle(self.rotation + angle);
+    }
+
+    /// Turn the image by `angle` radians keeping the complex point under `screen` fixed
+    pub fn rotate_at(&mut self, angle: f64, screen: [f64; 2]) {
+        let anchor = self.screen_to_offset(screen);
+        self.rotate_by(angle);
+        let moved = self.screen_to_offset(screen);
+        self.shift([anchor[0] - moved[0], anchor[1] - moved[1]]);
+    }
+
+    /// Multiply the zoom by `factor` about the visible center, within the zoom limits
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(limits::MIN_ZOOM, limits::MAX_ZOOM);
+    }
+
+    /// Multiply the zoom by `factor` keeping the complex point under `screen` fixed
+    pub fn zoom_at(&mut self, factor: f64, screen: [f64; 2]) {
+        let anchor = self.screen_to_offset(screen);
+        self.zoom_by(factor);
+        let moved = self.screen_to_offset(screen);
+        self.shift([anchor[0] - moved[0], anchor[1] - moved[1]]);
+    }
+
+    /// Box zoom between screen corners `min` and `max`, keeping the aspect ratio
+    /// Zooming in makes the box fill the visible area; zooming out shrinks the
+    /// visible area into the box
+    pub fn zoom_to_rect(&mut self, min: [f64; 2], max: [f64; 2], zoom_out: bool) {
+        let size = [(max[0] - min[0]).abs(), (max[1] - min[1]).abs()];
+        if size[0] <= 0.0 || size[1] <= 0.0 {
+            return;
+        }
+        let visible = self.visible_size();
+        let scale = (visible[0] / size[0]).min(visible[1] / size[1]);
+        let box_center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0];
+
+        if zoom_out {
+            // Pin the current visible center to the box center
+            self.zoom_by(1.0 / scale);
+            let moved = self.screen_to_offset(box_center);
+            self.shift([-moved[0], -moved[1]]);
+        } else {
+            // The visible center is NDC (0, 0), so the box center becomes the view center
+            self.center = self.screen_to_coordinate(box_center);
+            self.zoom_by(scale);
+        }
+    }
+}
+
+/// Wrap an angle in radians into (-pi, pi]
+pub fn wrap_angle(angle: f64) -> f64 {
+    use std::f64::consts::{PI, TAU};
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped == -PI {
+        PI
+    } else {
+        wrapped
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-fractal memory: the last view of every fractal type, kept across sessions
+//! Switching type parks the whole parameter set of the old type and brings back the new
+//! type's one, so going back returns exactly where we were
+
+use serde::{Deserialize, Serialize};
+
+use crate::files;
+use crate::fractal::{FractalParams, FractalType};
+use crate::scene::Scene;
+
+/// Current view memory document version
+pub const VIEWS_VERSION: u32 = 1;
+
+/// Name of the stored view memory document
+const STORE_NAME: &str = "views";
+
+/// On-disk layout, tagged with the format version it was written with
+#[derive(Serialize, Deserialize)]
+struct Document {
+    version: u32,
+    current: FractalType,
+    views: Vec<FractalParams>,
+}
+
+/// The view-defining fields of `params`; per-frame layout fields take their defaults
+fn snapshot(params: &FractalParams) -> FractalParams {
+    let defaults = FractalParams::default();
+    FractalParams {
+        resolution: defaults.resolution,
+        ui_offset: defaults.ui_offset,
+        ui_offset_y: defaults.ui_offset_y,
+        ..*params
+    }
+}
+
+/// The view a fractal type starts with before it has been visited
+pub fn initial_view(fractal_type: FractalType) -> FractalParams {
+    let mut params = FractalParams::default();
+    params.set_fractal_type(fractal_type);
+    params.color_scheme = fractal_type.default_color_scheme();
+    params.reset();
+    params
+}
+
+/// Last parameters seen for each fractal type (indexed by FractalType as u32)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FractalViews {
+    views: [Option<FractalParams>; 9],
+    current: FractalType,
+}
+
+impl FractalViews {
+    pub fn new() -> Self {
+        Self {
+            views: [None; 9],
+            current: FractalType::Mandelbrot,
+        }
+    }
+
+    /// Remember `params` as the view of its fractal type
+    pub fn remember(&mut self, params: &FractalParams) {
+        let fractal_type = params.get_fractal_type();
+        self.views[fractal_type as usize] = Some(snapshot(params));
+        self.current = fractal_type;
+    }
+
+    /// The remembered view of `fractal_type`, or its initial view if it was never visited
+    pub fn recall(&self, fractal_type: FractalType) -> FractalParams {
+        self.views[fractal_type as usize].unwrap_or_else(|| initial_view(fractal_type))
+    }
+
+    /// The remembered view of the fractal type that was shown last
+    pub fn current(&self) -> FractalParams {
+        self.recall(self.current)
+    }
+
+    /// Park the view in `params` and switch it to the remembered view of `fractal_type`,
+    /// keeping the per-frame layout fields
+    pub fn switch(&mut self, params: &mut FractalParams, fractal_type: FractalType) {
+        self.remember(params);
+        *params = FractalParams {
+            resolution: params.resolution,
+            ui_offset: params.ui_offset,
+            ui_offset_y: params.ui_offset_y,
+            ..self.recall(fractal_type)
+        };
+        self.current = fractal_type;
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        let document = Document {
+            version: VIEWS_VERSION,
+            current: self.current,
+            views: self.views.iter().flatten().copied().collect(),
+        };
+        serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to write views: {e}"))
+    }
+
+    /// Parse a view memory document
+    /// Each view passes the same validation as a scene file; invalid views are skipped
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document: Document = serde_json::from_str(text).map_err(|e| format!("Invalid views: {e}"))?;
+        if document.version == 0 || document.version > VIEWS_VERSION {
+            return Err(format!("Unsupported views version {}", document.version));
+        }
+
+        let mut views = Self::new();
+        for view in &document.views {
+            match Scene::new(view).validate() {
+                Ok(params) => views.views[params.fractal_type as usize] = Some(params),
+                Err(e) => log::warn!("Skipping view: {}", e),
+            }
+        }
+        views.current = document.current;
+        Ok(views)
+    }
+
+    /// Read the views remembered in earlier sessions, or start empty
+    pub fn load() -> Self {
+        let Some(text) = files::read_config(STORE_NAME) else {
+            return Self::new();
+        };
+        Self::from_json(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring stored views: {}", e);
+            Self::new()
+        })
+    }
+
+    /// Write the views back to the user's storage
+    pub fn store(&self) -> Result<(), String> {
+        files::write_config(STORE_NAME, &self.to_json()?)
+    }
+}
+
+impl Default for FractalViews {
+    fn default() -> Self {
+        Self::new()
+    }
+}
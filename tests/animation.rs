@@ -0,0 +1,201 @@
+use fractal_madness::animation::{ease_in_out_cubic, ease_out_cubic, Animator, FlightPath};
+use fractal_madness::{Coordinate, FractalParams, Viewport};
+
+const SIZE: (u32, u32) = (1280, 800);
+
+fn params() -> FractalParams {
+    FractalParams {
+        center: [-0.5, 0.0].map(Coordinate::from_f64),
+        zoom: 1.0,
+        resolution: [SIZE.0 as f32, SIZE.1 as f32],
+        ..FractalParams::default()
+    }
+}
+
+/// Tick at `fps` from `start` until `end` seconds
+fn run(animator: &mut Animator, params: &mut FractalParams, start: f64, end: f64, fps: f64) {
+    let mut t = start;
+    while t < end {
+        t = (t + 1.0 / fps).min(end);
+        animator.tick(params, SIZE, t);
+    }
+}
+
+#[test]
+fn easing_curves_span_unit_interval_monotonically() {
+    for ease in [ease_out_cubic, ease_in_out_cubic] {
+        assert_eq!(ease(0.0), 0.0);
+        assert_eq!(ease(1.0), 1.0);
+        assert_eq!(ease(-1.0), 0.0);
+        assert_eq!(ease(2.0), 1.0);
+        let samples: Vec<f64> = (0..=100).map(|i| ease(i as f64 / 100.0)).collect();
+        assert!(samples.windows(2).all(|w| w[1] >= w[0]));
+    }
+}
+
+#[test]
+fn zoom_eases_to_target_about_anchor() {
+    let mut params = params();
+    let anchor = [900.0, 200.0];
+    let fixed = Viewport::for_screen(&params, SIZE).screen_to_complex(anchor);
+
+    let mut animator = Animator::new();
+    animator.zoom_at(&params, 2.0, anchor, 0.0);
+    // A second wheel step while the first is running adds to the target
+    run(&mut animator, &mut params, 0.0, 0.05, 60.0);
+    assert!(params.zoom > 1.0 && params.zoom < 2.0);
+    animator.zoom_at(&params, 2.0, anchor, 0.05);
+    run(&mut animator, &mut params, 0.05, 1.0, 60.0);
+
+    assert!(!animator.is_active());
+    assert!((params.zoom - 4.0).abs() < 1e-5);
+    let after = Viewport::for_screen(&params, SIZE).screen_to_complex(anchor);
+    assert!((after[0] - fixed[0]).abs() < 1e-6 && (after[1] - fixed[1]).abs() < 1e-6);
+}
+
+#[test]
+fn momentum_is_frame_rate_independent_and_stops() {
+    let fling = |fps: f64| {
+        let mut params = params();
+        let mut animator = Animator::new();
+        for i in 0..5 {
+            animator.track_drag([20.0, -10.0], i as f64 * 0.01);
+        }
+        animator.release(0.045);
+        assert!(animator.is_active());
+        run(&mut animator, &mut params, 0.045, 3.0, fps);
+        assert!(!animator.is_active());
+        params.center_f64()
+    };
+
+    let (a, b) = (fling(30.0), fling(144.0));
+    assert!(a[0] < -0.5, "content dragged right moves the view left");
+    assert!((a[0] - b[0]).abs() < 1e-3 && (a[1] - b[1]).abs() < 1e-3);
+}
+
+#[test]
+fn slow_or_held_releases_do_not_fling() {
+    let mut animator = Animator::new();
+    animator.track_drag([1.0, 0.0], 0.0);
+    animator.track_drag([1.0, 0.0], 0.05);
+    animator.release(0.06);
+    assert!(!animator.is_active());
+
+    animator.track_drag([50.0, 0.0], 1.0);
+    animator.track_drag([50.0, 0.0], 1.01);
+    animator.release(1.5);
+    assert!(!animator.is_active());
+}
+
+#[test]
+fn outside_changes_cancel_animation() {
+    let mut params = params();
+    let mut animator = Animator::new();
+    animator.zoom_at(&params, 4.0, [640.0, 400.0], 0.0);
+    animator.tick(&mut params, SIZE, 0.02);
+
+    params.reset();
+    assert!(!animator.tick(&mut params, SIZE, 0.04));
+    assert!(!animator.is_active());
+}
+
+/// Distance from the view center to `point`, in visible heights
+fn screen_distance(path: &FlightPath, t: f64, point: [f64; 2]) -> f64 {
+    let (center, zoom) = path.at(t);
+    (center[0] - point[0]).hypot(center[1] - point[1]) * zoom / 2.0
+}
+
+#[test]
+fn flight_path_ends_exactly_and_never_loses_the_destination() {
+    let trips = [
+        ([-0.5, 0.0], 1.0, [-0.743643887, 0.131825904], 1e6),
+        ([-1.0, 0.0], 100.0, [0.3, 0.5], 100.0),
+        ([0.0, 0.0], 1e-3, [5.0, 0.0], 1e4),
+        ([0.25, 0.0], 1e4, [0.25, 0.0], 1.0),
+    ];
+    for (from, from_zoom, to, to_zoom) in trips {
+        let path = FlightPath::new(from, from_zoom, to, to_zoom);
+        assert_eq!(path.at(0.0).1, from_zoom);
+        assert_eq!(path.at(1.0), (to, to_zoom));
+
+        let start = screen_distance(&path, 0.0, to);
+        for i in 1..=1000 {
+            let d = screen_distance(&path, i as f64 / 1000.0, to);
+            assert!(d <= start + 1e-9, "destination drifted away at step {i}");
+        }
+    }
+}
+
+#[test]
+fn long_flights_zoom_out_on_the_way() {
+    // Both ends deep in, far apart: the path rises above either zoom
+    let path = FlightPath::new([-1.0, 0.0], 100.0, [0.3, 0.5], 100.0);
+    let (_, mid_zoom) = path.at(0.5);
+    assert!(mid_zoom < 10.0);
+    // The destination is on screen at the top of the arc
+    assert!(screen_distance(&path, 0.5, [0.3, 0.5]) < 0.5);
+}
+
+#[test]
+fn fly_to_blends_and_lands_on_target() {
+    let mut params = params();
+    let mut target = params;
+    target.set_center([-0.743644, 0.131826]);
+    target.zoom = 5000.0;
+    target.julia_c = [0.3, -0.2];
+    target.power = 3.0;
+    target.color_scheme = 7;
+
+    let mut animator = Animator::new();
+    animator.fly_to(&mut params, &target, 2.0, 0.0);
+    // Settings that cannot be blended switch right away
+    assert_eq!(params.color_scheme, 7);
+    assert_eq!(params.zoom, 1.0);
+
+    run(&mut animator, &mut params, 0.0, 1.0, 60.0);
+    assert!(params.julia_c[0] > -0.7 && params.julia_c[0] < 0.3);
+    assert!(params.power > 2.0 && params.power < 3.0);
+
+    run(&mut animator, &mut params, 1.0, 2.5, 60.0);
+    assert!(!animator.is_active());
+    assert_eq!(params, target);
+}
+
+#[test]
+fn deep_flights_travel_between_views_f64_cannot_tell_apart() {
+    let mut params = FractalParams { zoom: 1e82, ..params() };
+    let mut target = params;
+    target.center[0] = params.center[0] + Coordinate::from_f64(1e-80);
+    assert_eq!(params.center_f64(), target.center_f64());
+    let start = params;
+
+    let mut animator = Animator::new();
+    animator.fly_to(&mut params, &target, 2.0, 0.0);
+    run(&mut animator, &mut params, 0.0, 1.0, 60.0);
+    let traveled = (params.center[0] - start.center[0]).to_f64();
+    assert!(traveled > 1e-82 && traveled < 1e-80, "{traveled}");
+
+    run(&mut animator, &mut params, 1.0, 2.5, 60.0);
+    assert_eq!(params, target);
+}
+
+#[test]
+fn fly_to_without_duration_jumps_and_grab_interrupts() {
+    let mut params = params();
+    let mut target = params;
+    target.set_center([0.3, 0.1]);
+    target.zoom = 40.0;
+
+    let mut animator = Animator::new();
+    animator.fly_to(&mut params, &target, 0.0, 0.0);
+    assert_eq!(params, target);
+    assert!(!animator.is_active());
+
+    let mut params = self::params();
+    animator.fly_to(&mut params, &target, 1.0, 0.0);
+    run(&mut animator, &mut params, 0.0, 0.3, 60.0);
+    animator.grab();
+    let held = params;
+    run(&mut animator, &mut params, 0.3, 1.5, 60.0);
+    assert_eq!(params, held);
+}
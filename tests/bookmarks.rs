@@ -0,0 +1,104 @@
+use fractal_madness::bookmarks::{thumbnail, Bookmarks, ThumbnailJob, BOOKMARKS_VERSION, THUMBNAIL_SIZE};
+use fractal_madness::{Coordinate, FractalParams};
+
+fn at(x: f64) -> FractalParams {
+    FractalParams {
+        center: [x, 0.0].map(Coordinate::from_f64),
+        ..FractalParams::default()
+    }
+}
+
+fn names(bookmarks: &Bookmarks) -> Vec<&str> {
+    bookmarks.iter().map(|bookmark| bookmark.name.as_str()).collect()
+}
+
+#[test]
+fn json_round_trip_keeps_order_and_views() {
+    let mut bookmarks = Bookmarks::new();
+    bookmarks.add("Seahorse", &at(-0.75));
+    bookmarks.add("Elephant", &at(0.28));
+
+    let loaded = Bookmarks::from_json(&bookmarks.to_json().unwrap()).unwrap();
+    assert_eq!(loaded, bookmarks);
+    assert_eq!(names(&loaded), ["Seahorse", "Elephant"]);
+    assert_eq!(loaded.get(1).unwrap().params.center_f64(), [0.28, 0.0]);
+
+    // Thumbnail keys come back with the views but are not written out
+    let keys: Vec<&str> = loaded.iter().map(|bookmark| bookmark.key()).collect();
+    assert!(!keys[0].is_empty() && keys[0] != keys[1], "{keys:?}");
+    assert!(!bookmarks.to_json().unwrap().contains(keys[0]));
+}
+
+#[test]
+fn layout_fields_are_not_saved() {
+    let mut bookmarks = Bookmarks::new();
+    bookmarks.add("View", &FractalParams {
+        resolution: [300.0, 200.0],
+        ui_offset: 0.4,
+        ..at(1.0)
+    });
+    let saved = bookmarks.get(0).unwrap().params;
+    assert_eq!(saved.resolution, FractalParams::default().resolution);
+    assert_eq!(saved.ui_offset, 0.0);
+}
+
+#[test]
+fn rename_remove_and_reorder() {
+    let mut bookmarks = Bookmarks::new();
+    for name in ["a", "b", "c", "d"] {
+        bookmarks.add(name, &at(0.0));
+    }
+
+    bookmarks.move_to(0, 2);
+    assert_eq!(names(&bookmarks), ["b", "c", "a", "d"]);
+    bookmarks.move_to(3, 0);
+    assert_eq!(names(&bookmarks), ["d", "b", "c", "a"]);
+    bookmarks.move_to(1, 9);
+    assert_eq!(names(&bookmarks), ["d", "b", "c", "a"]);
+
+    bookmarks.rename(1, "  renamed ");
+    assert_eq!(bookmarks.remove(0).unwrap().name, "d");
+    assert!(bookmarks.remove(5).is_none());
+    assert_eq!(names(&bookmarks), ["renamed", "c", "a"]);
+}
+
+#[test]
+fn invalid_entries_are_skipped() {
+    let json = format!(
+        r#"{{"version":{BOOKMARKS_VERSION},"bookmarks":[
+            {{"name":"good","params":{{"center":[0.1,0.2],"zoom":2.0}}}},
+            {{"name":"bad","params":{{"zoom":-1.0}}}}
+        ]}}"#
+    );
+    let bookmarks = Bookmarks::from_json(&json).unwrap();
+    assert_eq!(names(&bookmarks), ["good"]);
+}
+
+#[test]
+fn future_versions_and_scenes_are_rejected() {
+    let future = format!(r#"{{"version":{},"bookmarks":[]}}"#, BOOKMARKS_VERSION + 1);
+    assert!(Bookmarks::from_json(&future).is_err());
+    assert!(Bookmarks::from_json(r#"{"version":1,"params":{}}"#).is_err());
+}
+
+#[test]
+fn thumbnail_has_requested_size() {
+    let [width, height] = THUMBNAIL_SIZE;
+    let pixels = thumbnail(&FractalParams { ui_offset: 0.5, ..FractalParams::default() });
+    assert_eq!(pixels.len(), (width * height * 4) as usize);
+    assert!(pixels.chunks(4).any(|px| px[..3] != pixels[..3]));
+}
+
+#[test]
+fn thumbnail_job_delivers_the_thumbnail_once() {
+    let params = at(-0.5);
+    let mut job = ThumbnailJob::start(&params);
+    let pixels = loop {
+        if let Some(pixels) = job.poll() {
+            break pixels;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    };
+    assert_eq!(pixels, thumbnail(&params));
+    assert_eq!(job.poll(), None);
+}
@@ -0,0 +1,141 @@
+use fractal_madness::bookmarks::Bookmarks;
+use fractal_madness::deeplink::{decode, encode};
+use fractal_madness::export::{params_from_text, params_to_text};
+use fractal_madness::presets::{parse_pack, PRESETS_VERSION};
+use fractal_madness::scene::Scene;
+use fractal_madness::{Coordinate, FractalParams, Viewport};
+
+/// A center 1e-25 off an f64, far finer than f64 can place it
+fn deep_center() -> [Coordinate; 2] {
+    let offset = Coordinate::from_f64(1e-25);
+    [
+        Coordinate::from_f64(-0.743_643_887_037_158) + offset,
+        Coordinate::from_f64(0.131_825_904_205_33) - offset,
+    ]
+}
+
+fn deep_params() -> FractalParams {
+    FractalParams {
+        center: deep_center(),
+        zoom: 1e10,
+        ..FractalParams::default()
+    }
+}
+
+/// `params` with the per-frame fields a file does not keep
+fn restore(loaded: FractalParams) -> FractalParams {
+    FractalParams { resolution: FractalParams::default().resolution, ..loaded }
+}
+
+#[test]
+fn decimal_text_round_trips() {
+    let [x, y] = deep_center();
+    for c in [x, y, -x, Coordinate::ZERO, Coordinate::from_f64(1e-25)] {
+        let text = c.to_string();
+        assert_eq!(text.parse::<Coordinate>(), Ok(c), "{text}");
+    }
+    // Values an f64 holds exactly read and write like one
+    assert_eq!(Coordinate::from_f64(-0.5).to_string(), "-0.5");
+    assert_eq!("0.1".parse::<Coordinate>(), Ok(Coordinate::from_f64(0.1)));
+    assert_eq!("1.5e-3".parse::<Coordinate>(), Ok(Coordinate::from_f64(1.5e-3)));
+
+    // Longer decimals are read past f64 precision
+    let long: Coordinate = "0.100000000000000000000000001".parse().unwrap();
+    let tenth: Coordinate = "0.100000000000000000000000000".parse().unwrap();
+    assert!(((long - tenth).to_f64() - 1e-27).abs() < 1e-40);
+    assert_eq!(long.to_decimal(27), "0.100000000000000000000000001");
+    assert_eq!(Coordinate::from_f64(-0.999_999_9).to_decimal(3), "-1.000");
+
+    for bad in ["", "-", "1.2.3", "abc", "inf", "1e", "5000000000", "12345678901.000000000000000000001"] {
+        assert!(bad.parse::<Coordinate>().is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn exports_round_trip_exact_centers() {
+    let params = deep_params();
+    assert_ne!(params.center, params.center_f64().map(Coordinate::from_f64));
+
+    assert_eq!(params_from_text(&params_to_text(&params)).map(restore), Ok(params));
+    assert_eq!(decode(&encode(&params)).map(restore), Some(params));
+
+    let scene = Scene::from_json(&Scene::new(&params).to_json().unwrap()).unwrap();
+    assert_eq!(restore(scene.validate().unwrap()), params);
+    let scene = Scene::from_ron(&Scene::new(&params).to_ron().unwrap()).unwrap();
+    assert_eq!(restore(scene.validate().unwrap()), params);
+
+    let mut bookmarks = Bookmarks::new();
+    bookmarks.add("deep", &params);
+    let loaded = Bookmarks::from_json(&bookmarks.to_json().unwrap()).unwrap();
+    assert_eq!(restore(loaded.get(0).unwrap().params), params);
+}
+
+#[test]
+fn shallow_centers_stay_plain_numbers() {
+    let json = Scene::new(&FractalParams::default()).to_json().unwrap();
+    assert!(json.contains("-0.5"), "{json}");
+    assert!(!json.contains("\"-0.5\""), "{json}");
+}
+
+#[test]
+fn presets_take_decimal_centers() {
+    let pack = format!(
+        r#"{{"version":{PRESETS_VERSION},"presets":[{{"name":"Deep","fractal_type":"Mandelbrot","center":["{}","{}"],"zoom":1e10}}]}}"#,
+        deep_center()[0],
+        deep_center()[1],
+    );
+    let presets = parse_pack(&pack).unwrap();
+    let mut params = FractalParams::default();
+    presets[0].apply(&mut params);
+    assert_eq!(params.center, deep_center());
+
+    let out_of_range = format!(r#"{{"version":{PRESETS_VERSION},"presets":[{{"name":"x","fractal_type":"Julia","center":[1e300,0],"zoom":1.0}}]}}"#);
+    assert!(parse_pack(&out_of_range).is_err());
+}
+
+#[test]
+fn panning_keeps_every_bit_at_depth() {
+    // Down to the zoom limit
+    for zoom in [1e30, 1e100] {
+        let mut viewport = Viewport {
+            center: deep_center(),
+            zoom,
+            rotation: 0.3,
+            resolution: [1280.0, 800.0],
+            offset: [0.0, 0.0],
+        };
+        let start = viewport.center;
+
+        viewport.pan(1.0, 0.0);
+        let moved = (viewport.center[0] - start[0]).to_f64().hypot((viewport.center[1] - start[1]).to_f64());
+        assert!((moved / viewport.pixel_size() - 1.0).abs() < 1e-12, "moved {moved} at {zoom:e}");
+
+        for _ in 0..100 {
+            viewport.pan(3.0, -2.0);
+        }
+        for _ in 0..100 {
+            viewport.pan(-3.0, 2.0);
+        }
+        viewport.pan(-1.0, 0.0);
+        assert_eq!(viewport.center, start, "{zoom:e}");
+    }
+}
+
+#[test]
+fn zooming_keeps_the_anchor_at_depth() {
+    let mut viewport = Viewport {
+        center: deep_center(),
+        zoom: 1e9,
+        rotation: -1.1,
+        resolution: [1280.0, 800.0],
+        offset: [0.0, 0.0],
+    };
+    let anchor = [200.0, 650.0];
+    for _ in 0..20 {
+        let before = viewport.screen_to_coordinate(anchor);
+        viewport.zoom_at(1.1, anchor);
+        let after = viewport.screen_to_coordinate(anchor);
+        let drift = [(after[0] - before[0]).to_f64(), (after[1] - before[1]).to_f64()];
+        assert!(drift[0].hypot(drift[1]) < 1e-6 * viewport.pixel_size(), "{drift:?}");
+    }
+}
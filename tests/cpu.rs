@@ -0,0 +1,82 @@
+use fractal_madness::cpu::iterate::{
+    iterate_burning_ship, iterate_julia, iterate_mandelbrot, iterate_newton, iterate_phoenix, iterate_tricorn,
+};
+use fractal_madness::cpu::{linear_to_srgb8, render, sample, shade, NewtonRoots, Vec2};
+use fractal_madness::views::initial_view;
+use fractal_madness::{FractalParams, FractalType};
+
+const MAX_ITER: u32 = 100;
+const ESCAPE_RADIUS: f32 = 4.0;
+const WIDTH: u32 = 32;
+const HEIGHT: u32 = 20;
+
+#[test]
+fn known_points_escape_when_expected() {
+    let zero = Vec2::new(0.0, 0.0);
+    // 0 and -2 are fixed or periodic and never leave the set
+    assert_eq!(iterate_mandelbrot(zero, 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+    assert_eq!(iterate_mandelbrot(Vec2::new(-2.0, 0.0), 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+    assert_eq!(iterate_burning_ship(zero, 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+    assert_eq!(iterate_tricorn(zero, 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+    assert_eq!(iterate_phoenix(zero, zero, 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+
+    // c = 1: 0 -> 1 -> 2 -> 5, and |5|^2 is past the escape radius squared
+    let one = iterate_mandelbrot(Vec2::new(1.0, 0.0), 2.0, MAX_ITER, ESCAPE_RADIUS);
+    assert_eq!(one, Vec2::new(3.0, 25.0));
+
+    // Julia with c = 0 squares z: inside the unit circle it stays, from 2 it reaches 4 in one step
+    assert_eq!(iterate_julia(Vec2::new(0.5, 0.0), zero, 2.0, MAX_ITER, ESCAPE_RADIUS).x, MAX_ITER as f32);
+    assert_eq!(iterate_julia(Vec2::new(2.0, 0.0), zero, 2.0, MAX_ITER, ESCAPE_RADIUS), Vec2::new(1.0, 16.0));
+
+    // Newton starting on a root is done at once; near a root it converges within a few steps
+    let (result, root) = iterate_newton(Vec2::new(1.0, 0.0), 3.0, 1.0, MAX_ITER, ESCAPE_RADIUS);
+    assert_eq!((result.x, root), (0.0, 0.0));
+    let (result, root) = iterate_newton(Vec2::new(-0.45, 0.85), 3.0, 1.0, MAX_ITER, ESCAPE_RADIUS);
+    assert!(result.x > 0.0 && result.x < 10.0, "{result:?}");
+    assert!((root - 1.0 / 3.0).abs() < 1e-6, "{root}");
+}
+
+#[test]
+fn points_in_the_set_shade_black() {
+    let params = FractalParams { max_iter: MAX_ITER, ..FractalParams::default() };
+    assert_eq!(shade(&params, Vec2::new(MAX_ITER as f32, 0.0), -1.0), [0.0, 0.0, 0.0]);
+    assert_ne!(shade(&params, Vec2::new(3.0, 25.0), -1.0), [0.0, 0.0, 0.0]);
+}
+
+/// One pixel of each type's starting view, recorded from the reference renderer
+#[test]
+fn reference_pixels_per_fractal_type() {
+    let expected: [(FractalType, [u8; 4]); 9] = [
+        (FractalType::Mandelbrot, [255, 130, 149, 255]),
+        (FractalType::Julia, [255, 132, 146, 255]),
+        (FractalType::BurningShip, [49, 0, 0, 255]),
+        (FractalType::Tricorn, [26, 0, 255, 255]),
+        (FractalType::Celtic, [111, 96, 66, 255]),
+        (FractalType::BuffaloJulia, [193, 245, 64, 255]),
+        (FractalType::CelticJulia, [132, 255, 153, 255]),
+        (FractalType::Newton, [218, 6, 232, 255]),
+        (FractalType::Phoenix, [110, 12, 158, 255]),
+    ];
+    let (x, y) = (5, 4);
+    for (fractal_type, rgba) in expected {
+        let mut params = initial_view(fractal_type);
+        params.resolution = [WIDTH as f32, HEIGHT as f32];
+        let pixels = render(&params, WIDTH, HEIGHT);
+        let start = (y * WIDTH + x) as usize * 4;
+        assert_eq!(pixels[start..start + 4], rgba, "{}", fractal_type.name());
+    }
+}
+
+#[test]
+fn render_fills_every_pixel_from_sample() {
+    let params = FractalParams { resolution: [WIDTH as f32, HEIGHT as f32], ..FractalParams::default() };
+    let pixels = render(&params, WIDTH, HEIGHT);
+    assert_eq!(pixels.len(), (WIDTH * HEIGHT * 4) as usize);
+    let roots = NewtonRoots::new(&params);
+
+    for (i, px) in pixels.chunks(4).enumerate() {
+        let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+        let rgb = sample(&params, &roots, None, x, y, WIDTH, HEIGHT).map(linear_to_srgb8);
+        assert_eq!(px, [rgb[0], rgb[1], rgb[2], 255], "pixel {x},{y}");
+    }
+}
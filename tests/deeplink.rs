@@ -0,0 +1,36 @@
+use fractal_madness::deeplink::{decode, encode};
+use fractal_madness::{ColorScheme, FractalParams, FractalType};
+
+#[test]
+fn round_trips_view_fields() {
+    let mut params = FractalParams::default();
+    params.set_fractal_type(FractalType::BurningShip);
+    params.set_center([-1.7623, -0.028_1]);
+    params.zoom = 1234.5;
+    params.max_iter = 900;
+    params.color_scheme = ColorScheme::DeepSea as u32;
+    params.set_invert(true);
+
+    let hash = encode(&params);
+    assert!(hash.starts_with('#'));
+    assert!(!hash.contains(' '));
+    assert_eq!(decode(&hash), Some(params));
+}
+
+#[test]
+fn falls_back_on_bad_hashes() {
+    assert_eq!(decode(""), None);
+    assert_eq!(decode("#"), None);
+    assert_eq!(decode("#section-2"), None);
+    assert_eq!(decode("#version=99&zoom=2"), None);
+    assert_eq!(decode("#fractal_type=Nope"), None);
+    assert_eq!(decode("#zoom=%ZZ"), None);
+}
+
+#[test]
+fn ignores_unknown_keys_and_clamps_values() {
+    let params = decode("#zoom=1e300&max_iter=1&future_key=1").unwrap();
+    assert_eq!(params.zoom, 1e100);
+    assert_eq!(params.max_iter, 10);
+    assert_eq!(params.center, FractalParams::default().center);
+}
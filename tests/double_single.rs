@@ -0,0 +1,110 @@
+use fractal_madness::cpu::double_single::{ds_add, ds_mul, ds_sub, iterate_ds, pixel_to_complex_ds, Ds};
+use fractal_madness::cpu::{iterate, pixel_to_complex, NewtonRoots};
+use fractal_madness::deeplink::{decode, encode};
+use fractal_madness::scene::Scene;
+use fractal_madness::{FractalParams, FractalType, Viewport};
+
+const WIDTH: u32 = 48;
+const HEIGHT: u32 = 32;
+
+/// A view far past where f32 pixelates
+fn deep_view(fractal_type: FractalType, center: [f64; 2], zoom: f64) -> FractalParams {
+    let mut params = FractalParams::default();
+    params.set_fractal_type(fractal_type);
+    params.set_center(center);
+    params.zoom = zoom;
+    params.max_iter = 500;
+    params.resolution = [WIDTH as f32, HEIGHT as f32];
+    params
+}
+
+/// Reference escape-time iteration in f64
+fn iterate_f64(params: &FractalParams, c: [f64; 2]) -> u32 {
+    let escape2 = (params.escape_radius * params.escape_radius) as f64;
+    let (mut x, mut y) = (0.0_f64, 0.0_f64);
+    let mut i = 0;
+    while i < params.max_iter && x * x + y * y < escape2 {
+        if params.get_fractal_type() == FractalType::BurningShip {
+            (x, y) = (x.abs(), y.abs());
+        }
+        (x, y) = (x * x - y * y + c[0], 2.0 * x * y + c[1]);
+        i += 1;
+    }
+    i
+}
+
+/// Share of pixels whose iteration count matches the f64 reference, for double-single and f32
+fn agreement(params: &FractalParams) -> (f64, f64) {
+    let viewport = Viewport::for_screen(params, (WIDTH, HEIGHT));
+    let roots = NewtonRoots::new(params);
+    let (mut ds_hits, mut f32_hits) = (0, 0);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let (px, py) = (x as f32, y as f32);
+            let expected = iterate_f64(params, viewport.screen_to_complex([px as f64 + 0.5, py as f64 + 0.5])) as f32;
+
+            let ds = iterate_ds(params, pixel_to_complex_ds(params, px, py, WIDTH, HEIGHT));
+            let (single, _) = iterate::iterate(params, &roots, pixel_to_complex(params, px, py, WIDTH, HEIGHT));
+            ds_hits += (ds.x == expected) as u32;
+            f32_hits += (single.x == expected) as u32;
+        }
+    }
+    let total = (WIDTH * HEIGHT) as f64;
+    (ds_hits as f64 / total, f32_hits as f64 / total)
+}
+
+#[test]
+fn arithmetic_matches_f64() {
+    let values = [1.0 / 3.0, -0.743_643_887_037_158_7, 1.234_567_890_123_4e-5, std::f64::consts::E];
+    for a in values {
+        for b in values {
+            let (da, db) = (Ds::from_f64(a), Ds::from_f64(b));
+            let (a, b) = (da.to_f64(), db.to_f64());
+            let scale = a.abs().max(b.abs()).max(1e-30);
+            assert!((ds_add(da, db).to_f64() - (a + b)).abs() <= 1e-14 * scale, "{a} + {b}");
+            assert!((ds_sub(da, db).to_f64() - (a - b)).abs() <= 1e-14 * scale, "{a} - {b}");
+            assert!((ds_mul(da, db).to_f64() - a * b).abs() <= 1e-14 * (a * b).abs().max(1e-30), "{a} * {b}");
+        }
+    }
+}
+
+#[test]
+fn switches_on_past_the_pixel_threshold() {
+    let center = [-0.743_643_887_037_158, 0.131_825_904_205_33];
+    assert!(!deep_view(FractalType::Mandelbrot, center, 10.0).high_precision());
+    assert!(deep_view(FractalType::Mandelbrot, center, 1e8).high_precision());
+    assert!(!deep_view(FractalType::Newton, center, 1e8).high_precision());
+
+    let mut cubic = deep_view(FractalType::Mandelbrot, center, 1e8);
+    cubic.power = 3.0;
+    assert!(!cubic.high_precision());
+}
+
+#[test]
+fn deep_mandelbrot_matches_f64_reference() {
+    // A Misiurewicz point: detail at every scale, but few pixels deep enough to be chaotic
+    let params = deep_view(FractalType::Mandelbrot, [-0.101_096_363_845_62, 0.956_286_510_809_14], 1e9);
+    let (ds, single) = agreement(&params);
+    assert!(ds >= 0.95, "double-single agreed on {:.0}% of pixels", ds * 100.0);
+    assert!(single < 0.5, "f32 agreed on {:.0}% of pixels", single * 100.0);
+}
+
+#[test]
+fn deep_burning_ship_matches_f64_reference() {
+    let params = deep_view(FractalType::BurningShip, [-1.762_413_532_367_217, -0.028_011_935_432_613], 1e8);
+    let (ds, _) = agreement(&params);
+    assert!(ds >= 0.95, "double-single agreed on {:.0}% of pixels", ds * 100.0);
+}
+
+#[test]
+fn full_precision_center_round_trips() {
+    let params = deep_view(FractalType::Mandelbrot, [-0.101_096_363_845_62, 0.956_286_510_809_14], 1e9);
+    assert_ne!(params.uniform().center_lo, [0.0, 0.0]);
+    let [x, y] = params.center_f64();
+    assert!((x + 0.101_096_363_845_62).abs() < 1e-15 && (y - 0.956_286_510_809_14).abs() < 1e-15);
+
+    let restore = |loaded: FractalParams| FractalParams { resolution: params.resolution, ..loaded };
+    assert_eq!(decode(&encode(&params)).map(restore), Some(params));
+    let scene = Scene::from_json(&Scene::new(&params).to_json().unwrap()).unwrap();
+    assert_eq!(restore(scene.validate().unwrap()), params);
+}
@@ -0,0 +1,54 @@
+use fractal_madness::export::{encode_png, params_from_text, params_to_text, read_png_params};
+use fractal_madness::{cpu, ColorScheme, FractalParams, FractalType};
+
+const WIDTH: u32 = 24;
+const HEIGHT: u32 = 16;
+
+/// A view that sets every field the parameter text carries away from its default
+fn view() -> FractalParams {
+    let mut params = FractalParams {
+        zoom: 37.5,
+        max_iter: 777,
+        power: 3.0,
+        escape_radius: 8.0,
+        color_scheme: ColorScheme::Ocean as u32,
+        julia_c: [-0.4, 0.6],
+        rotation: 0.3,
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        ..FractalParams::default()
+    };
+    params.set_fractal_type(FractalType::Julia);
+    params.set_center([0.123_456_789_012_345_6, -0.987_654_321]);
+    params.set_invert(true);
+    params
+}
+
+#[test]
+fn png_carries_the_view_it_was_rendered_from() {
+    let params = view();
+    let pixels = cpu::render(&params, WIDTH, HEIGHT);
+    let bytes = encode_png(&params, WIDTH, HEIGHT, &pixels).unwrap();
+
+    let loaded = read_png_params(&bytes).unwrap();
+    assert_eq!(FractalParams { resolution: params.resolution, ..loaded }, params);
+    assert_eq!(loaded, params_from_text(&params_to_text(&params)).unwrap());
+
+    // The image itself survives too
+    let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+    let mut decoded = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut decoded).unwrap();
+    assert_eq!((frame.width, frame.height), (WIDTH, HEIGHT));
+    assert_eq!(&decoded[..frame.buffer_size()], pixels.as_slice());
+}
+
+#[test]
+fn png_without_parameters_is_rejected() {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, 1, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.write_header().unwrap().write_image_data(&[0, 0, 0, 255]).unwrap();
+    }
+    assert!(read_png_params(&bytes).is_err());
+    assert!(read_png_params(b"not a png").is_err());
+}
@@ -0,0 +1,52 @@
+use fractal_madness::cpu;
+use fractal_madness::headless::{tile_params, TileRenderer};
+use fractal_madness::FractalParams;
+
+const WIDTH: u32 = 40;
+const HEIGHT: u32 = 24;
+
+#[test]
+fn cpu_fallback_matches_the_reference_renderer() {
+    let params = FractalParams { max_iter: 120, ..FractalParams::default() };
+    let mut renderer = TileRenderer::cpu();
+    let pixels = pollster::block_on(renderer.render(&params, WIDTH, HEIGHT));
+    assert_eq!(pixels.len(), (WIDTH * HEIGHT * 4) as usize);
+
+    // The fallback renders at the requested size whatever resolution `params` carried
+    let expected = cpu::render(&FractalParams { resolution: [WIDTH as f32, HEIGHT as f32], ..params }, WIDTH, HEIGHT);
+    assert_eq!(pixels, expected);
+
+    let mut bands = Vec::new();
+    pollster::block_on(renderer.render_bands(&params, WIDTH, HEIGHT, |band| {
+        bands.extend_from_slice(band);
+        Ok(())
+    }))
+    .unwrap();
+    assert_eq!(bands, expected);
+}
+
+#[test]
+fn stitched_tiles_match_a_single_render() {
+    const POSTER: (u32, u32) = (64, 32);
+    let (tile_width, tile_height) = (POSTER.0 / 2, POSTER.1 / 2);
+    // Zoom, center and pixel positions are all short binary fractions here, so every tile places
+    // its pixels at exactly the same points as the full image and the results must be identical
+    let params = FractalParams {
+        zoom: 2.0,
+        max_iter: 200,
+        resolution: [POSTER.0 as f32, POSTER.1 as f32],
+        ..FractalParams::default()
+    };
+    let single = cpu::render(&params, POSTER.0, POSTER.1);
+
+    let mut stitched = vec![0u8; single.len()];
+    for (x0, y0) in [(0, 0), (tile_width, 0), (0, tile_height), (tile_width, tile_height)] {
+        let tile = tile_params(&params, POSTER.0, POSTER.1, x0, y0, tile_width, tile_height);
+        let pixels = cpu::render(&tile, tile_width, tile_height);
+        for (row, src) in pixels.chunks(tile_width as usize * 4).enumerate() {
+            let start = ((y0 as usize + row) * POSTER.0 as usize + x0 as usize) * 4;
+            stitched[start..start + src.len()].copy_from_slice(src);
+        }
+    }
+    assert_eq!(stitched, single);
+}
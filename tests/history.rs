@@ -0,0 +1,113 @@
+use fractal_madness::history::History;
+use fractal_madness::{Coordinate, FractalParams};
+
+fn at(x: f64) -> FractalParams {
+    FractalParams {
+        center: [x, 0.0].map(Coordinate::from_f64),
+        ..FractalParams::default()
+    }
+}
+
+/// Observe `params` every frame at 60 fps from `start` until `end` seconds
+fn hold(history: &mut History, params: &FractalParams, busy: bool, start: f64, end: f64) {
+    let mut t = start;
+    while t < end {
+        history.observe(params, busy, t);
+        t += 1.0 / 60.0;
+    }
+}
+
+#[test]
+fn a_drag_undoes_in_one_step() {
+    let mut history = History::new();
+    hold(&mut history, &at(0.0), false, 0.0, 1.0);
+    assert!(!history.can_undo());
+
+    // Many small moves while the pointer is held
+    for i in 1..=30 {
+        history.observe(&at(i as f64 * 0.01), true, 1.0 + i as f64 / 60.0);
+    }
+    hold(&mut history, &at(0.3), false, 2.0, 3.0);
+
+    let mut params = at(0.3);
+    assert!(history.undo(&mut params));
+    assert_eq!(params.center_f64(), [0.0, 0.0]);
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn undo_then_redo_round_trips() {
+    let mut history = History::new();
+    hold(&mut history, &at(0.0), false, 0.0, 1.0);
+    hold(&mut history, &at(1.0), false, 1.0, 2.0);
+    hold(&mut history, &at(2.0), false, 2.0, 3.0);
+
+    let mut params = at(2.0);
+    assert!(history.undo(&mut params));
+    assert!(history.undo(&mut params));
+    assert_eq!(params.center_f64(), [0.0, 0.0]);
+    assert!(history.redo(&mut params));
+    assert_eq!(params.center_f64(), [1.0, 0.0]);
+    assert!(history.redo(&mut params));
+    assert_eq!(params.center_f64(), [2.0, 0.0]);
+    assert!(!history.redo(&mut params));
+}
+
+#[test]
+fn undo_keeps_layout_fields() {
+    let mut history = History::new();
+    hold(&mut history, &at(0.0), false, 0.0, 1.0);
+    hold(&mut history, &at(1.0), false, 1.0, 2.0);
+
+    let mut params = FractalParams {
+        resolution: [640.0, 480.0],
+        ui_offset: 300.0,
+        ..at(1.0)
+    };
+    assert!(history.undo(&mut params));
+    assert_eq!(params.center_f64(), [0.0, 0.0]);
+    assert_eq!(params.resolution, [640.0, 480.0]);
+    assert_eq!(params.ui_offset, 300.0);
+}
+
+#[test]
+fn unsettled_change_is_undoable_immediately() {
+    let mut history = History::new();
+    hold(&mut history, &at(0.0), false, 0.0, 1.0);
+    history.observe(&at(1.0), false, 1.0);
+    assert!(history.can_undo());
+
+    let mut params = at(1.0);
+    assert!(history.undo(&mut params));
+    assert_eq!(params.center_f64(), [0.0, 0.0]);
+    assert!(history.redo(&mut params));
+    assert_eq!(params.center_f64(), [1.0, 0.0]);
+}
+
+#[test]
+fn new_edit_clears_redo() {
+    let mut history = History::new();
+    hold(&mut history, &at(0.0), false, 0.0, 1.0);
+    hold(&mut history, &at(1.0), false, 1.0, 2.0);
+
+    let mut params = at(1.0);
+    history.undo(&mut params);
+    assert!(history.can_redo());
+    hold(&mut history, &at(5.0), false, 2.0, 3.0);
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn history_is_bounded() {
+    let mut history = History::new();
+    for i in 0..500 {
+        hold(&mut history, &at(i as f64), false, i as f64, i as f64 + 1.0);
+    }
+    let mut params = at(499.0);
+    let mut steps = 0;
+    while history.undo(&mut params) {
+        steps += 1;
+    }
+    assert!(steps < 500);
+    assert!(params.center_f64()[0] > 0.0);
+}
@@ -0,0 +1,159 @@
+use fractal_madness::animation::Animator;
+use fractal_madness::cpu::pixel_to_complex;
+use fractal_madness::input::InputState;
+use fractal_madness::{FractalParams, Viewport};
+use winit::event::TouchPhase;
+
+const SIZE: (u32, u32) = (1280, 800);
+
+/// A turned view on a wide window with the side panel open, so the panel offset, the aspect
+/// ratio and the rotation all take part in the mapping
+fn params() -> FractalParams {
+    let mut params = FractalParams {
+        zoom: 3.0,
+        rotation: 0.4,
+        ..FractalParams::default()
+    };
+    params.set_center([-0.7, 0.2]);
+    let mut viewport = Viewport::for_screen(&params, SIZE);
+    viewport.set_panel_width(280.0);
+    viewport.apply(&mut params);
+    params
+}
+
+/// Distance between two screen points' complex coordinates, in pixels of `params`
+fn drift(params: &FractalParams, a: [f64; 2], b: [f64; 2]) -> f64 {
+    let viewport = Viewport::for_screen(params, SIZE);
+    let (a, b) = (viewport.screen_to_complex(a), viewport.screen_to_complex(b));
+    (a[0] - b[0]).hypot(a[1] - b[1]) / viewport.pixel_size()
+}
+
+#[test]
+fn wheel_zoom_keeps_the_point_under_the_cursor() {
+    let mut params = params();
+    let mut input = InputState::new();
+    input.handle_cursor_move(900.0, 250.0);
+    let anchor = input.zoom_anchor(&Viewport::for_screen(&params, SIZE));
+    assert_eq!(anchor, [900.0, 250.0]);
+
+    // The mapping agrees with the shader's: pixel (900, 250) is centered on (900.5, 250.5)
+    let fixed = Viewport::for_screen(&params, SIZE).screen_to_complex(anchor);
+    let shader = pixel_to_complex(&params, 900.0, 250.0, SIZE.0, SIZE.1);
+    let pixel = Viewport::for_screen(&params, SIZE).pixel_size();
+    let expected = Viewport::for_screen(&params, SIZE).screen_to_complex([900.5, 250.5]);
+    assert!((shader.x as f64 - expected[0]).abs() < 1e-3 * pixel + 1e-7, "{shader:?} {expected:?}");
+    assert!((shader.y as f64 - expected[1]).abs() < 1e-3 * pixel + 1e-7, "{shader:?} {expected:?}");
+
+    // One eased wheel step, as the event loop runs it; the point stays put on every frame
+    let mut animator = Animator::new();
+    animator.zoom_at(&params, 1.5, anchor, 0.0);
+    let mut t = 0.0;
+    while animator.is_active() {
+        t += 1.0 / 60.0;
+        animator.tick(&mut params, SIZE, t);
+        let viewport = Viewport::for_screen(&params, SIZE);
+        let now = viewport.screen_to_complex(anchor);
+        let moved = (now[0] - fixed[0]).hypot(now[1] - fixed[1]) / viewport.pixel_size();
+        assert!(moved < 1e-9, "drifted {moved} px at t = {t}");
+    }
+    assert!((params.zoom - 4.5).abs() < 1e-5, "{}", params.zoom);
+}
+
+#[test]
+fn zoom_without_a_cursor_keeps_the_visible_center() {
+    let mut params = params();
+    let mut input = InputState::new();
+    input.handle_cursor_move(10.0, 10.0);
+    input.handle_cursor_left();
+
+    let mut viewport = Viewport::for_screen(&params, SIZE);
+    let anchor = input.zoom_anchor(&viewport);
+    viewport.zoom_at(2.0, anchor);
+    viewport.apply(&mut params);
+    assert_eq!(params.center, self::params().center);
+    assert_eq!(drift(&params, anchor, viewport.offset_to_screen([0.0, 0.0])), 0.0);
+}
+
+/// Feed finger `id` moving from `from` to `to` in `steps` moves, applying each gesture to `params`
+fn swipe(input: &mut InputState, params: &mut FractalParams, id: u64, from: (f32, f32), to: (f32, f32), steps: u32) {
+    for step in 1..=steps {
+        let t = step as f32 / steps as f32;
+        let (x, y) = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+        if let Some(gesture) = input.handle_touch(id, TouchPhase::Moved, x, y) {
+            let mut viewport = Viewport::for_screen(params, SIZE);
+            gesture.apply(&mut viewport);
+            viewport.apply(params);
+        }
+    }
+}
+
+/// Complex point under `screen`
+fn under(params: &FractalParams, screen: (f32, f32)) -> [f64; 2] {
+    Viewport::for_screen(params, SIZE).screen_to_complex([screen.0 as f64, screen.1 as f64])
+}
+
+/// Distance in pixels between complex points `a` and `b` in the view of `params`
+fn pixels_apart(params: &FractalParams, a: [f64; 2], b: [f64; 2]) -> f64 {
+    (a[0] - b[0]).hypot(a[1] - b[1]) / Viewport::for_screen(params, SIZE).pixel_size()
+}
+
+#[test]
+fn one_finger_drags_the_content() {
+    let mut params = params();
+    let mut input = InputState::new();
+    let start = (400.0, 300.0);
+    let grabbed = under(&params, start);
+
+    assert_eq!(input.handle_touch(1, TouchPhase::Started, start.0, start.1), None);
+    assert!(input.is_interacting());
+    swipe(&mut input, &mut params, 1, start, (520.0, 220.0), 6);
+    assert_eq!((params.zoom, params.rotation), (self::params().zoom, self::params().rotation));
+    assert!(pixels_apart(&params, under(&params, (520.0, 220.0)), grabbed) < 1e-3);
+
+    input.handle_touch(1, TouchPhase::Ended, 520.0, 220.0);
+    assert!(!input.is_interacting());
+    assert_eq!(input.handle_touch(1, TouchPhase::Moved, 600.0, 200.0), None);
+}
+
+#[test]
+fn two_fingers_pinch_turn_and_pan_together() {
+    let mut params = params();
+    let mut input = InputState::new();
+    let (a, b) = ((500.0, 400.0), (700.0, 400.0));
+    let (grabbed_a, grabbed_b) = (under(&params, a), under(&params, b));
+    input.handle_touch(1, TouchPhase::Started, a.0, a.1);
+    input.handle_touch(2, TouchPhase::Started, b.0, b.1);
+
+    // Spread to twice the distance, turning the pair a quarter turn clockwise on screen
+    let gesture = input.handle_touch(2, TouchPhase::Moved, 500.0, 800.0).unwrap();
+    assert!((gesture.zoom - 2.0).abs() < 1e-6, "{gesture:?}");
+    assert!((gesture.rotation + std::f32::consts::FRAC_PI_2).abs() < 1e-6, "{gesture:?}");
+    assert_eq!(gesture.center, (500.0, 600.0));
+    assert_eq!(gesture.pan, (-100.0, 200.0));
+    input.handle_touch(2, TouchPhase::Moved, b.0, b.1);
+
+    // Done in steps and with both fingers moving, the content under each finger stays with it
+    let (a2, b2) = ((450.0, 350.0), (620.0, 640.0));
+    for _ in 0..3 {
+        swipe(&mut input, &mut params, 2, b, b2, 5);
+        swipe(&mut input, &mut params, 1, a, a2, 5);
+        swipe(&mut input, &mut params, 1, a2, a, 5);
+        swipe(&mut input, &mut params, 2, b2, b, 5);
+    }
+    assert!(pixels_apart(&params, under(&params, a), grabbed_a) < 1e-2);
+    assert!(pixels_apart(&params, under(&params, b), grabbed_b) < 1e-2);
+
+    swipe(&mut input, &mut params, 2, b, b2, 5);
+    swipe(&mut input, &mut params, 1, a, a2, 5);
+    assert!(pixels_apart(&params, under(&params, a2), grabbed_a) < 1e-2);
+    assert!(pixels_apart(&params, under(&params, b2), grabbed_b) < 1e-2);
+    let spread: f32 = (b2.0 - a2.0).hypot(b2.1 - a2.1) / 200.0;
+    assert!((params.zoom / self::params().zoom - spread as f64).abs() < 1e-4, "{}", params.zoom);
+    let turn = (b2.1 - a2.1).atan2(b2.0 - a2.0);
+    assert!((params.rotation - (self::params().rotation - turn)).abs() < 1e-4, "{}", params.rotation);
+
+    // A third finger does not take part
+    input.handle_touch(3, TouchPhase::Started, 100.0, 100.0);
+    assert_eq!(input.handle_touch(3, TouchPhase::Moved, 150.0, 100.0), None);
+    assert_eq!(input.touch_count(), 3);
+}
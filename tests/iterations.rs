@@ -0,0 +1,240 @@
+use fractal_madness::export::{params_from_text, params_to_text};
+use fractal_madness::iterations::{
+    auto_iterations, biased, choose, escape_counts, sample_limit, sampled_iterations, zoom_iterations, AutoIterations,
+    EscapeSampler, CLIPPED_SHARE, RESAMPLE_SHIFT, RESAMPLE_ZOOM, SAMPLE_GRID, SAMPLE_ROWS_PER_FRAME,
+};
+use fractal_madness::perturbation::ReferenceOrbits;
+use fractal_madness::scene::Scene;
+use fractal_madness::{Coordinate, FractalParams, FractalType};
+
+/// Mandelbrot in the seahorse valley at `zoom`, with auto iterations on
+fn seahorse(zoom: f64) -> FractalParams {
+    let mut params = FractalParams {
+        center: [-0.743_643_887_037_158, 0.131_825_904_205_33].map(Coordinate::from_f64),
+        zoom,
+        ..FractalParams::default()
+    };
+    params.set_auto_iterations(true);
+    params
+}
+
+/// Updates `auto` needs to sample the whole grid
+const UPDATES: u32 = SAMPLE_GRID[1].div_ceil(SAMPLE_ROWS_PER_FRAME);
+
+/// Update `auto` until the grid of a view shallow enough to need no orbit is sampled;
+/// true if `params` changed
+fn settle(auto: &mut AutoIterations, params: &mut FractalParams) -> bool {
+    let mut orbits = ReferenceOrbits::new();
+    (0..UPDATES).fold(false, |changed, _| auto.update(params, false, &mut orbits) | changed)
+}
+
+#[test]
+fn zoom_estimate_grows_with_depth() {
+    assert_eq!(zoom_iterations(1.0), 100);
+    assert_eq!(zoom_iterations(0.01), 100);
+    assert!(zoom_iterations(1e3) < zoom_iterations(1e6));
+    assert!(sample_limit(1e3) > zoom_iterations(1e3));
+    assert_eq!(sample_limit(1e10), 4 * zoom_iterations(1e10));
+    // Deep views go past the slider's usual limit
+    assert!(zoom_iterations(1e100) > 100_000);
+}
+
+#[test]
+fn choice_covers_boundary_samples() {
+    const LIMIT: u32 = 1000;
+    // Left half inside the set; the column next to it escapes slowly, the rest quickly
+    let columns = 10;
+    let mut counts = Vec::new();
+    for y in 0..10 {
+        for x in 0..columns {
+            counts.push(match x {
+                0..=4 => LIMIT,
+                5 => 100 + y * 10,
+                _ => 5,
+            });
+        }
+    }
+    // The slowest boundary sample escapes, and the fast samples away from the set do not count
+    assert_eq!(choose(&counts, columns as usize, LIMIT), Some(191));
+
+    // Without interior samples every escaping one counts
+    let flat: Vec<u32> = (1..=100).collect();
+    assert_eq!(choose(&flat, 10, LIMIT), Some(99));
+
+    assert_eq!(choose(&[LIMIT; 12], 4, LIMIT), None);
+}
+
+#[test]
+fn few_boundary_samples_hit_the_chosen_count() {
+    let [columns, rows] = SAMPLE_GRID.map(|n| n as usize);
+    for zoom in [1.0, 10.0, 1e5] {
+        let params = seahorse(zoom);
+        let chosen = sampled_iterations(&params);
+        let limit = sample_limit(zoom);
+
+        let counts = escape_counts(&params, columns as u32, rows as u32, limit);
+        let inside = |x: usize, y: usize| x < columns && y < rows && counts[y * columns + x] >= limit;
+        let (mut boundary, mut clipped) = (0, 0);
+        for y in 0..rows {
+            for x in 0..columns {
+                let near = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+                if !inside(x, y) && near.iter().any(|&(nx, ny)| inside(nx, ny)) {
+                    boundary += 1;
+                    clipped += (counts[y * columns + x] >= chosen) as u32;
+                }
+            }
+        }
+        assert!(boundary > 0, "zoom {zoom}");
+        assert!(
+            (clipped as f32) <= CLIPPED_SHARE * boundary as f32,
+            "zoom {zoom}: {clipped} of {boundary} clipped at {chosen}"
+        );
+    }
+
+    assert!(sampled_iterations(&seahorse(1e5)) > sampled_iterations(&seahorse(1.0)));
+}
+
+#[test]
+fn views_inside_the_set_fall_back_to_the_zoom_estimate() {
+    let mut params = seahorse(1e3);
+    params.set_center([-0.1, 0.0]);
+    assert_eq!(sampled_iterations(&params), zoom_iterations(1e3));
+
+    let newton = FractalParams { fractal_type: FractalType::Newton as u32, ..seahorse(1.0) };
+    assert!(auto_iterations(&newton) < 100);
+}
+
+#[test]
+fn bias_scales_the_count() {
+    assert_eq!(biased(400, 1.0, 10000), 400);
+    assert_eq!(biased(400, 2.5, 10000), 1000);
+    assert_eq!(biased(20, 0.25, 10000), 10);
+    assert_eq!(biased(8000, 4.0, 10000), 10000);
+    assert_eq!(biased(8000, 4.0, 1_000_000), 32000);
+
+    let mut params = seahorse(1e3);
+    params.iteration_bias = 2.0;
+    assert_eq!(auto_iterations(&params), biased(sampled_iterations(&params), 2.0, 10000));
+}
+
+#[test]
+fn tracker_follows_the_view_once_it_settles() {
+    let mut auto = AutoIterations::new();
+    let mut orbits = ReferenceOrbits::new();
+
+    let mut params = FractalParams { max_iter: 77, ..seahorse(1e3) };
+    params.set_auto_iterations(false);
+    assert!(!auto.update(&mut params, false, &mut orbits));
+    assert_eq!(params.max_iter, 77);
+
+    params.set_auto_iterations(true);
+    assert!(!auto.update(&mut params, true, &mut orbits));
+    assert_eq!(params.max_iter, 77);
+    // The count stays put until the last rows are sampled
+    for _ in 1..UPDATES {
+        assert!(!auto.update(&mut params, false, &mut orbits));
+        assert_eq!(params.max_iter, 77);
+    }
+    assert!(auto.update(&mut params, false, &mut orbits));
+    assert_eq!(params.max_iter, auto_iterations(&params));
+    assert!(!auto.update(&mut params, false, &mut orbits));
+
+    // The bias needs no new samples
+    params.iteration_bias = 0.5;
+    assert!(auto.update(&mut params, false, &mut orbits));
+    assert_eq!(params.max_iter, auto_iterations(&params));
+
+    // A view that changes halfway through is sampled again from the start
+    params.zoom = 1e5;
+    auto.update(&mut params, false, &mut orbits);
+    params.zoom = 1e4;
+    settle(&mut auto, &mut params);
+    assert_eq!(params.max_iter, auto_iterations(&params));
+}
+
+#[test]
+fn small_moves_keep_the_samples() {
+    let mut auto = AutoIterations::new();
+    let mut orbits = ReferenceOrbits::new();
+    let mut params = seahorse(1e3);
+    settle(&mut auto, &mut params);
+    let sampled = params.max_iter;
+
+    // Panning and zooming a little keeps the count without sampling again
+    let nudge = |params: &mut FractalParams| {
+        params.zoom *= RESAMPLE_ZOOM.sqrt();
+        // The view is 2 / zoom tall
+        params.center[0] = params.center[0] + Coordinate::from_f64(RESAMPLE_SHIFT / params.zoom);
+    };
+    nudge(&mut params);
+    assert!(!auto.update(&mut params, false, &mut orbits));
+    assert_eq!(params.max_iter, sampled);
+
+    // A view nudged halfway through sampling keeps the rows sampled so far
+    params.zoom = 1e5;
+    let expected = auto_iterations(&params);
+    assert!(!auto.update(&mut params, false, &mut orbits));
+    nudge(&mut params);
+    for _ in 2..UPDATES {
+        assert!(!auto.update(&mut params, false, &mut orbits));
+    }
+    assert!(auto.update(&mut params, false, &mut orbits));
+    assert_eq!(params.max_iter, expected);
+}
+
+#[test]
+fn deep_views_wait_for_the_shared_orbit() {
+    let mut auto = AutoIterations::new();
+    let mut orbits = ReferenceOrbits::new();
+    let mut params = seahorse(1e12);
+    assert!(params.perturbation());
+    let expected = auto_iterations(&params);
+
+    // Nothing is sampled until the orbit is computed off the caller
+    let mut updates = 0;
+    while !auto.update(&mut params, false, &mut orbits) {
+        updates += 1;
+        assert!(updates < 6000, "no count for {params:?}");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(updates >= UPDATES);
+    assert_eq!(params.max_iter, expected);
+
+    // The renderer draws against the same orbit
+    assert!(orbits.request(&params).is_some());
+}
+
+#[test]
+fn sampler_fills_the_grid_a_few_rows_at_a_time() {
+    let params = seahorse(1e3);
+    let [columns, rows] = SAMPLE_GRID;
+    let limit = sample_limit(params.zoom);
+    let mut sampler = EscapeSampler::new(&params, columns, rows, limit);
+    assert!(!sampler.sample_rows(SAMPLE_ROWS_PER_FRAME));
+    assert_eq!(sampler.counts().len(), (columns * SAMPLE_ROWS_PER_FRAME) as usize);
+    while !sampler.sample_rows(SAMPLE_ROWS_PER_FRAME) {}
+    assert!(sampler.is_done());
+    assert_eq!(sampler.counts(), escape_counts(&params, columns, rows, limit));
+}
+
+#[test]
+fn auto_mode_is_saved_with_the_view() {
+    let mut params = seahorse(1e3);
+    params.iteration_bias = 1.5;
+    let text = params_to_text(&params);
+    assert!(text.contains("iteration_bias=1.5"), "{text}");
+    let loaded = params_from_text(&text).unwrap();
+    assert!(loaded.auto_iterations());
+    assert_eq!(loaded.iteration_bias, 1.5);
+
+    // The bias is left out of manual views
+    assert!(!params_to_text(&FractalParams::default()).contains("iteration_bias"));
+
+    params.iteration_bias = 50.0;
+    let valid = Scene::new(&params).validate().unwrap();
+    assert!(valid.auto_iterations());
+    assert_eq!(valid.iteration_bias, 4.0);
+
+    params.iteration_bias = f32::NAN;
+    assert!(Scene::new(&params).validate().is_err());
+}
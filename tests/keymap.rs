@@ -0,0 +1,85 @@
+use fractal_madness::keymap::{Action, Keymap};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+fn char_key(text: &str) -> Key {
+    Key::Character(text.into())
+}
+
+#[test]
+fn keys_are_named_by_layout_and_chord() {
+    let none = ModifiersState::empty();
+    assert_eq!(Keymap::key_name(&char_key("w"), none).as_deref(), Some("W"));
+    assert_eq!(Keymap::key_name(&Key::Named(NamedKey::ArrowLeft), none).as_deref(), Some("ArrowLeft"));
+    assert_eq!(Keymap::key_name(&char_key("z"), ModifiersState::CONTROL).as_deref(), Some("Ctrl+Z"));
+    let ctrl_shift = ModifiersState::CONTROL | ModifiersState::SHIFT;
+    assert_eq!(Keymap::key_name(&char_key("Z"), ctrl_shift).as_deref(), Some("Ctrl+Shift+Z"));
+    assert_eq!(Keymap::key_name(&char_key("z"), ModifiersState::ALT), None);
+
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action_for(&char_key("w"), none), Some(Action::PanUp));
+    assert_eq!(keymap.action_for(&char_key("z"), ModifiersState::CONTROL), Some(Action::Undo));
+    assert_eq!(keymap.action_for(&char_key("z"), ctrl_shift), Some(Action::Redo));
+    assert_eq!(keymap.action_for(&char_key("z"), none), None);
+}
+
+#[test]
+fn rebinding_moves_an_action_to_new_keys() {
+    let mut keymap = Keymap::default();
+    keymap.unbind(Action::ZoomIn);
+    assert_eq!(keymap.keys_for(Action::ZoomIn).count(), 0);
+    assert_eq!(keymap.action_for(&char_key("+"), ModifiersState::empty()), None);
+
+    // Single characters match whatever case they were bound in
+    keymap.bind("i", Action::ZoomIn);
+    keymap.bind("ctrl+i", Action::ZoomOut);
+    keymap.bind("Ctrl+PageUp", Action::ZoomIn);
+    assert_eq!(keymap.keys_for(Action::ZoomIn).collect::<Vec<_>>(), ["I", "Ctrl+PageUp"]);
+    assert_eq!(keymap.keys_for(Action::ZoomOut).collect::<Vec<_>>(), ["-", "Ctrl+I"]);
+    assert_eq!(keymap.action_for(&char_key("i"), ModifiersState::empty()), Some(Action::ZoomIn));
+    let page_up = Key::Named(NamedKey::PageUp);
+    assert_eq!(keymap.action_for(&page_up, ModifiersState::CONTROL), Some(Action::ZoomIn));
+    assert_eq!(keymap.action_for(&page_up, ModifiersState::empty()), None);
+}
+
+#[test]
+fn binding_a_taken_key_replaces_its_action() {
+    let mut keymap = Keymap::default();
+    assert_eq!(keymap.keys_for(Action::PanUp).collect::<Vec<_>>(), ["ArrowUp", "W"]);
+
+    keymap.bind("w", Action::ZoomIn);
+    assert_eq!(keymap.action_for(&char_key("w"), ModifiersState::empty()), Some(Action::ZoomIn));
+    assert_eq!(keymap.keys_for(Action::PanUp).collect::<Vec<_>>(), ["ArrowUp"]);
+    assert!(keymap.keys_for(Action::ZoomIn).any(|key| key == "W"));
+
+    // Binding a key to the action it already has changes nothing else
+    let before = keymap.clone();
+    keymap.bind("W", Action::ZoomIn);
+    assert_eq!(keymap.keys_for(Action::ZoomIn).count(), before.keys_for(Action::ZoomIn).count());
+}
+
+#[test]
+fn bindings_round_trip_through_the_stored_document() {
+    let mut keymap = Keymap::default();
+    keymap.unbind(Action::Reset);
+    keymap.bind("Backspace", Action::Reset);
+    keymap.bind("Ctrl+Shift+r", Action::NextColorScheme);
+
+    let text = keymap.to_json().unwrap();
+    assert_eq!(Keymap::from_json(&text).unwrap(), keymap);
+    assert_eq!(Keymap::from_json(&Keymap::default().to_json().unwrap()).unwrap(), Keymap::default());
+}
+
+#[test]
+fn loading_normalizes_and_resolves_conflicts() {
+    let text = r#"{"version": 1, "bindings": [["x", "PanLeft"], ["ctrl+x", "Undo"], ["X", "PanRight"]]}"#;
+    let keymap = Keymap::from_json(text).unwrap();
+    let x = char_key("x");
+    assert_eq!(keymap.action_for(&x, ModifiersState::empty()), Some(Action::PanRight));
+    assert_eq!(keymap.action_for(&x, ModifiersState::CONTROL), Some(Action::Undo));
+    assert_eq!(keymap.keys_for(Action::PanLeft).count(), 0);
+
+    assert!(Keymap::from_json(r#"{"version": 2, "bindings": []}"#).is_err());
+    assert!(Keymap::from_json(r#"{"version": 0, "bindings": []}"#).is_err());
+    assert!(Keymap::from_json(r#"{"version": 1, "bindings": [["X", "Explode"]]}"#).is_err());
+    assert!(Keymap::from_json("not json").is_err());
+}
@@ -0,0 +1,171 @@
+use fractal_madness::cpu::iterate::{iterate_newton, iterate_newton_roots, newton_roots};
+use fractal_madness::cpu::{self, NewtonRoots, Vec2};
+use fractal_madness::deeplink::{decode, encode};
+use fractal_madness::scene::Scene;
+use fractal_madness::{FractalParams, FractalType};
+
+const MAX_ITER: u32 = 200;
+
+fn custom_newton(roots: &[[f32; 2]]) -> FractalParams {
+    let mut params = FractalParams::default();
+    params.set_fractal_type(FractalType::Newton);
+    params.set_roots(roots);
+    params
+}
+
+/// Root turn reached from `start`, or None if it did not converge
+fn basin(start: Vec2, power: f32) -> Option<f32> {
+    let (_, root) = iterate_newton(start, power, 1.0, MAX_ITER, 4.0);
+    (root >= 0.0).then_some(root)
+}
+
+#[test]
+fn whole_powers_have_one_root_per_degree() {
+    for n in 2..=8 {
+        let roots = newton_roots(n as f32);
+        assert_eq!(roots.len(), n, "power {n}");
+        for (root, _) in &roots {
+            assert!((root.length() - 1.0).abs() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn render_roots_follow_the_params() {
+    let mut params = custom_newton(&[]);
+    params.power = 5.0;
+    assert_eq!(NewtonRoots::new(&params), newton_roots(5.0));
+    assert_eq!(newton_roots(5.0).turns(), [0.6, 0.8, 0.0, 0.2, 0.4]);
+
+    let custom = custom_newton(&[[0.5, 0.5], [-1.0, 0.0], [0.0, -2.0], [1.0, 1.0]]);
+    let roots = NewtonRoots::new(&custom);
+    assert_eq!(roots.points(), [Vec2::new(0.5, 0.5), Vec2::new(-1.0, 0.0), Vec2::new(0.0, -2.0), Vec2::new(1.0, 1.0)]);
+    assert_eq!(roots.turns(), [0.0, 0.25, 0.5, 0.75]);
+}
+
+#[test]
+fn uniform_carries_the_roots() {
+    let mut params = custom_newton(&[]);
+    params.power = 8.0;
+    let uniform = params.uniform();
+    let roots = newton_roots(8.0);
+    assert_eq!(uniform.unity_root_count, 8);
+    for (i, (root, turn)) in roots.iter().enumerate() {
+        assert_eq!((uniform.newton_roots[i], uniform.root_turns[i]), ([root.x, root.y], *turn));
+    }
+    // Fractional powers stay within the slots
+    params.power = 7.9;
+    assert_eq!(params.uniform().unity_root_count as usize, newton_roots(7.9).len());
+
+    let custom = custom_newton(&[[0.5, 0.5], [-1.0, 0.0]]).uniform();
+    assert_eq!((custom.root_count, custom.unity_root_count), (2, 0));
+    assert_eq!(custom.newton_roots[..2], [[0.5, 0.5], [-1.0, 0.0]]);
+    assert_eq!(custom.root_turns[..2], [0.0, 0.5]);
+}
+
+#[test]
+fn every_root_of_unity_is_found_for_high_powers() {
+    for n in 4..=8 {
+        let power = n as f32;
+        for k in 0..n {
+            // Start just off each root; the iteration must settle on that root, not one of the first three
+            let angle = 2.0 * std::f32::consts::PI * k as f32 / power + 0.05;
+            let start = Vec2::new(1.1 * angle.cos(), 1.1 * angle.sin());
+            let turn = basin(start, power).unwrap_or_else(|| panic!("power {n}, root {k} did not converge"));
+            let expected = k as f32 / power;
+            assert!((turn - expected).abs() < 1e-5, "power {n}, root {k}: got turn {turn}");
+        }
+    }
+}
+
+#[test]
+fn basin_mode_colors_roots_apart() {
+    let mut params = FractalParams::default();
+    params.set_fractal_type(FractalType::Newton);
+    params.power = 5.0;
+    params.set_basins(true);
+
+    let roots = NewtonRoots::new(&params);
+    let colors: Vec<_> = roots
+        .iter()
+        .map(|(root, _)| {
+            let (result, turn) = cpu::iterate::iterate(&params, &roots, *root * 1.05);
+            cpu::shade(&params, result, turn)
+        })
+        .collect();
+    for (i, a) in colors.iter().enumerate() {
+        assert!(a.iter().any(|v| *v > 0.0), "root {i} is black");
+        for b in &colors[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn other_fractals_report_no_root() {
+    let params = FractalParams::default();
+    let (_, root) = cpu::iterate::iterate(&params, &NewtonRoots::new(&params), Vec2::new(0.3, 0.5));
+    assert_eq!(root, -1.0);
+}
+
+#[test]
+fn custom_roots_attract_nearby_points() {
+    let roots = [Vec2::new(0.5, 0.5), Vec2::new(-1.2, 0.1), Vec2::new(0.3, -0.9), Vec2::new(2.0, 1.5)];
+    for relaxation in [1.0, 0.7] {
+        for (i, root) in roots.iter().enumerate() {
+            let start = *root + Vec2::new(0.05, -0.03);
+            let (result, turn) = iterate_newton_roots(start, &roots, relaxation, MAX_ITER);
+            assert_eq!(turn, i as f32 / roots.len() as f32, "root {i}, a = {relaxation}");
+            assert!(result.x < MAX_ITER as f32);
+        }
+    }
+}
+
+#[test]
+fn relaxation_slows_convergence() {
+    let roots = [Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0), Vec2::new(0.0, 1.0)];
+    let start = Vec2::new(0.8, 0.1);
+    let (full, _) = iterate_newton_roots(start, &roots, 1.0, MAX_ITER);
+    let (damped, _) = iterate_newton_roots(start, &roots, 0.5, MAX_ITER);
+    assert!(damped.x > full.x);
+}
+
+#[test]
+fn cube_roots_of_unity_match_classic_newton() {
+    let roots: Vec<[f32; 2]> = newton_roots(3.0).iter().map(|(r, _)| [r.x, r.y]).collect();
+    let custom = custom_newton(&roots);
+    let mut classic = custom;
+    classic.set_roots(&[]);
+    classic.power = 3.0;
+
+    for start in [Vec2::new(0.7, 0.2), Vec2::new(-0.4, 0.9), Vec2::new(-0.6, -0.7)] {
+        let (custom_result, _) = cpu::iterate::iterate(&custom, &NewtonRoots::new(&custom), start);
+        let (classic_result, _) = cpu::iterate::iterate(&classic, &NewtonRoots::new(&classic), start);
+        assert!((custom_result.x - classic_result.x).abs() <= 1.0, "{start:?}");
+    }
+}
+
+#[test]
+fn roots_survive_scenes_and_links() {
+    let mut params = custom_newton(&[[0.5, -0.25], [-1.0, 0.75], [0.0, 1.5]]);
+    params.relaxation = 0.8;
+
+    let scene = Scene::from_json(&Scene::new(&params).to_json().unwrap()).unwrap();
+    assert_eq!(scene.validate().unwrap(), params);
+    assert_eq!(decode(&encode(&params)), Some(params));
+}
+
+#[test]
+fn validation_clamps_roots_and_drops_unused_slots() {
+    let mut params = custom_newton(&[[9.0, -9.0], [0.5, 0.5]]);
+    params.newton_roots[5] = [3.0, 3.0];
+    params.relaxation = 7.0;
+
+    let valid = Scene::new(&params).validate().unwrap();
+    assert_eq!(valid.roots(), [[4.0, -4.0], [0.5, 0.5]]);
+    assert_eq!(valid.newton_roots[5], [0.0, 0.0]);
+    assert_eq!(valid.relaxation, 2.0);
+
+    params.newton_roots[1] = [f32::NAN, 0.0];
+    assert!(Scene::new(&params).validate().is_err());
+}
@@ -0,0 +1,438 @@
+use fractal_madness::cpu::perturbation::iterate_perturbed;
+use fractal_madness::cpu::{self, linear_to_srgb8, NewtonRoots, Vec2};
+use fractal_madness::export::{params_from_text, params_to_text};
+use fractal_madness::fixed::Fixed;
+use fractal_madness::perturbation::{
+    reference_orbit, series_approximation, PerturbationParams, ReferenceOrbit, ReferenceOrbits, Series, REFERENCE_ROWS,
+    REFERENCE_WIDTH,
+};
+use fractal_madness::{Coordinate, FractalParams, FractalType, Viewport};
+
+const WIDTH: u32 = 24;
+const HEIGHT: u32 = 16;
+const ESCAPE_RADIUS: f32 = 4.0;
+
+/// Exact escape count of `c`, iterated entirely in fixed point
+fn exact_count<const N: usize>(fractal_type: FractalType, c: [Fixed<N>; 2], max_iter: u32) -> u32 {
+    reference_orbit(fractal_type, c, max_iter, ESCAPE_RADIUS).len() as u32 - 1
+}
+
+/// Series approximation for the test view, probed at its corners, edge midpoints and middle
+fn view_series(orbit: &[[f32; 2]], uniform: &PerturbationParams, max_iter: u32) -> Series {
+    let aspect = WIDTH as f64 / HEIGHT as f64;
+    let scale = uniform.pixel_scale as f64;
+    let probes: Vec<[f64; 2]> = (0..9)
+        .map(|i| [((i % 3) as f64 - 1.0) * aspect * scale, ((i / 3) as f64 - 1.0) * scale])
+        .collect();
+    let pixel = 2.0 * scale / HEIGHT as f64;
+    series_approximation(orbit, uniform.scale_exp, &probes, pixel, max_iter, ESCAPE_RADIUS)
+}
+
+/// Share of pixels where perturbation (with the series approximation if `series`) matches the
+/// exact count, and the distinct counts in the view
+fn agreement<const N: usize>(
+    fractal_type: FractalType,
+    center: [Fixed<N>; 2],
+    zoom: f64,
+    max_iter: u32,
+    series: bool,
+) -> (f64, Vec<u32>) {
+    let orbit = reference_orbit(fractal_type, center, max_iter, ESCAPE_RADIUS);
+    let mut uniform = PerturbationParams::new(zoom, orbit.len());
+    if series {
+        uniform = uniform.with_series(&view_series(&orbit, &uniform, max_iter));
+    }
+    let aspect = WIDTH as f32 / HEIGHT as f32;
+
+    let mut hits = 0;
+    let mut counts = Vec::new();
+    for py in 0..HEIGHT {
+        for px in 0..WIDTH {
+            let offset = Vec2::new(
+                ((px as f32 + 0.5) / WIDTH as f32 - 0.5) * 2.0 * aspect,
+                ((py as f32 + 0.5) / HEIGHT as f32 - 0.5) * 2.0,
+            );
+            let perturbed = iterate_perturbed(fractal_type, &orbit, &uniform, offset, max_iter, ESCAPE_RADIUS);
+            let c = [
+                center[0] + Fixed::from_f64(offset.x as f64 / zoom),
+                center[1] + Fixed::from_f64(offset.y as f64 / zoom),
+            ];
+            let exact = exact_count(fractal_type, c, max_iter);
+            hits += (perturbed.x as u32 == exact) as u32;
+            counts.push(exact);
+        }
+    }
+    counts.sort_unstable();
+    counts.dedup();
+    (hits as f64 / (WIDTH * HEIGHT) as f64, counts)
+}
+
+/// Returns the distinct exact counts of the view
+fn assert_agrees<const N: usize>(fractal_type: FractalType, center: [Fixed<N>; 2], zoom: f64, max_iter: u32) -> Vec<u32> {
+    let (share, counts) = agreement(fractal_type, center, zoom, max_iter, false);
+    assert!(counts.len() >= 5, "{fractal_type:?} at {zoom:e} is featureless ({counts:?})");
+    assert!(share >= 0.95, "{fractal_type:?} at {zoom:e} agreed on {:.0}% of pixels", share * 100.0);
+    counts
+}
+
+#[test]
+fn fixed_arithmetic_matches_f64() {
+    let values = [0.0, 1.0 / 3.0, -0.743_643_887_037_158_7, 1.234_567_890_123_4e-5, -std::f64::consts::E];
+    for a in values {
+        for b in values {
+            let (fa, fb) = (Fixed::<4>::from_f64(a), Fixed::<4>::from_f64(b));
+            assert_eq!(fa.to_f64(), a);
+            let scale = a.abs().max(b.abs());
+            assert!(((fa + fb).to_f64() - (a + b)).abs() <= f64::EPSILON * scale, "{a} + {b}");
+            assert!(((fa - fb).to_f64() - (a - b)).abs() <= f64::EPSILON * scale, "{a} - {b}");
+            assert!(((fa * fb).to_f64() - a * b).abs() <= f64::EPSILON * (a * b).abs(), "{a} * {b}");
+        }
+    }
+    assert_eq!(Fixed::<4>::from_f64(-0.0), Fixed::ZERO);
+    assert_eq!(Fixed::<4>::from_f64(-1.5).resize::<8>().resize::<4>().to_f64(), -1.5);
+}
+
+#[test]
+fn fixed_keeps_bits_f64_cannot() {
+    // 1 + 2^-200 - 1 is lost in f64 but not with 8 limbs (224 fraction bits)
+    let tiny = Fixed::<8>::from_f64(2.0_f64.powi(-200));
+    let one = Fixed::<8>::from_f64(1.0);
+    assert_eq!((one + tiny - one).to_f64(), 2.0_f64.powi(-200));
+    assert_eq!(((one + tiny) * (one - tiny)).to_f64(), 1.0);
+}
+
+#[test]
+fn switches_on_past_double_single() {
+    let mut params = FractalParams {
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        zoom: 1e8,
+        ..Default::default()
+    };
+    assert!(params.high_precision() && !params.perturbation());
+    params.zoom = 1e10;
+    assert!(params.perturbation());
+
+    params.set_fractal_type(FractalType::Celtic);
+    assert!(!params.perturbation());
+    params.set_fractal_type(FractalType::Tricorn);
+    params.power = 3.0;
+    assert!(!params.perturbation());
+}
+
+#[test]
+fn reference_orbit_is_reused_until_the_view_changes() {
+    let mut params = FractalParams::default();
+    params.set_center([-0.101_096_363_845_62, 0.956_286_510_809_14]);
+    params.zoom = 1e10;
+    let orbit = ReferenceOrbit::new(&params);
+    assert!(orbit.matches(&params));
+
+    let mut rotated = params;
+    rotated.rotation = 1.0;
+    assert!(orbit.matches(&rotated));
+
+    // Pans keep the reference while it is on screen
+    let mut viewport = Viewport::from_params(&params);
+    viewport.pan(300.0, -200.0);
+    let mut moved = params;
+    viewport.apply(&mut moved);
+    assert!(orbit.matches(&moved));
+    viewport.pan(params.resolution[0] as f64, 0.0);
+    viewport.apply(&mut moved);
+    assert!(!orbit.matches(&moved));
+
+    // Zooms keep it until the orbit needs more limbs
+    let mut deeper = params;
+    deeper.zoom = 1e30;
+    assert!(orbit.matches(&deeper));
+    deeper.zoom = 1e60;
+    assert!(!orbit.matches(&deeper));
+
+    // Shorter orbits do not need a new one, longer ones do unless the reference escaped
+    let mut inside = FractalParams { zoom: 1e10, ..FractalParams::default() };
+    inside.set_center([-0.5, 0.1]);
+    let full = ReferenceOrbit::new(&inside);
+    inside.max_iter -= 1;
+    assert!(full.matches(&inside));
+    inside.max_iter += 2;
+    assert!(!full.matches(&inside));
+    assert!(orbit.points.len() <= params.max_iter as usize);
+    let mut longer = params;
+    longer.max_iter *= 10;
+    assert!(orbit.matches(&longer));
+
+    let mut other = params;
+    other.set_fractal_type(FractalType::Tricorn);
+    assert!(!orbit.matches(&other) && !orbit.same_formula(&other));
+}
+
+/// `orbits.request(params)` once the orbit has been computed, polling for up to a minute
+fn wait_for(orbits: &mut ReferenceOrbits, params: &FractalParams) -> std::sync::Arc<ReferenceOrbit> {
+    for _ in 0..6000 {
+        if let Some(orbit) = orbits.request(params) {
+            return orbit;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("no orbit for {params:?}");
+}
+
+#[test]
+fn orbits_are_computed_off_the_caller() {
+    let mut params = FractalParams::default();
+    params.set_center([-0.101_096_363_845_62, 0.956_286_510_809_14]);
+    params.zoom = 1e10;
+
+    let mut orbits = ReferenceOrbits::new();
+    assert!(orbits.request(&params).is_none());
+    assert!(orbits.latest(&params).is_none());
+    let orbit = wait_for(&mut orbits, &params);
+    assert_eq!(orbit.points, ReferenceOrbit::new(&params).points);
+
+    // Leaving it behind starts a new one; the old one stands in until then
+    let mut moved = params;
+    moved.set_center([-0.1, 0.95]);
+    assert!(orbits.request(&moved).is_none());
+    assert!(std::sync::Arc::ptr_eq(&orbits.latest(&moved).unwrap(), &orbit));
+    let mut other = moved;
+    other.set_fractal_type(FractalType::BurningShip);
+    assert!(orbits.latest(&other).is_none());
+    assert!(wait_for(&mut orbits, &moved).matches(&moved));
+
+    let mut blocking = ReferenceOrbits::blocking();
+    assert!(blocking.request(&params).is_some());
+}
+
+#[test]
+fn mandelbrot_at_1e12() {
+    let center = [-0.101_096_363_845_62, 0.956_286_510_809_14].map(Fixed::<4>::from_f64);
+    assert_agrees(FractalType::Mandelbrot, center, 1e12, 500);
+}
+
+#[test]
+fn mandelbrot_at_1e100() {
+    // c = i is a Misiurewicz point: there is structure around it at every depth
+    let center = [0.0, 1.0].map(Fixed::<16>::from_f64);
+    assert_agrees(FractalType::Mandelbrot, center, 1e100, 1000);
+}
+
+#[test]
+fn burning_ship_at_1e40() {
+    // The ship's tip c = -2 lands on the fixed point 2
+    let center = [-2.0, 0.0].map(Fixed::<8>::from_f64);
+    assert_agrees(FractalType::BurningShip, center, 1e40, 500);
+}
+
+#[test]
+fn tricorn_at_1e40() {
+    let center = [-2.0, 0.0].map(Fixed::<8>::from_f64);
+    assert_agrees(FractalType::Tricorn, center, 1e40, 500);
+}
+
+#[test]
+fn params_render_past_the_f32_zoom_range() {
+    let params = FractalParams {
+        center: [0.0, 1.0].map(Coordinate::from_f64),
+        zoom: 1e45,
+        max_iter: 1000,
+        flags: 0,
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        ..FractalParams::default()
+    };
+    assert!(params.perturbation());
+    assert_eq!(params_from_text(&params_to_text(&params)).unwrap().zoom, 1e45);
+
+    let pixels = cpu::render(&params, WIDTH, HEIGHT);
+    let mut hits = 0;
+    let mut colors: Vec<&[u8]> = pixels.chunks(4).collect();
+    for (i, px) in pixels.chunks(4).enumerate() {
+        let (x, y) = (i as u32 % WIDTH, i as u32 / WIDTH);
+        let offset = cpu::pixel_offset(&params, x as f32, y as f32, WIDTH, HEIGHT);
+        let c = [
+            params.center[0] + Coordinate::from_f64(offset.x as f64 / params.zoom),
+            params.center[1] + Coordinate::from_f64(offset.y as f64 / params.zoom),
+        ];
+        let exact = exact_count(FractalType::Mandelbrot, c, params.max_iter);
+        let rgb = cpu::shade(&params, Vec2::new(exact as f32, 0.0), -1.0).map(linear_to_srgb8);
+        hits += (px[..3] == rgb) as u32;
+    }
+    colors.sort_unstable();
+    colors.dedup();
+    assert!(colors.len() >= 5, "featureless: {colors:?}");
+    assert!(hits as f64 >= 0.95 * (WIDTH * HEIGHT) as f64, "{hits} pixels match");
+}
+
+#[test]
+fn panned_views_render_against_the_old_reference() {
+    let params = FractalParams {
+        center: [0.0, 1.0].map(Coordinate::from_f64),
+        zoom: 1e30,
+        max_iter: 1000,
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        ..FractalParams::default()
+    };
+    let orbit = ReferenceOrbit::new(&params);
+
+    // Pan the reference point close to a corner of the view
+    let mut viewport = Viewport::from_params(&params);
+    viewport.pan(-0.4 * WIDTH as f64, 0.4 * HEIGHT as f64);
+    let mut panned = params;
+    viewport.apply(&mut panned);
+    assert!(orbit.matches(&panned));
+    let uniform = orbit.uniform(&panned);
+    assert_ne!(uniform.reference_offset, [0.0; 2]);
+
+    let roots = NewtonRoots::new(&panned);
+    let mut hits = 0;
+    let mut counts = Vec::new();
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            let (result, _) = cpu::iterate_pixel(&panned, &roots, Some((&orbit, &uniform)), x, y, WIDTH, HEIGHT);
+            let offset = cpu::pixel_offset(&panned, x as f32, y as f32, WIDTH, HEIGHT);
+            let c = [
+                panned.center[0] + Coordinate::from_f64(offset.x as f64 / panned.zoom),
+                panned.center[1] + Coordinate::from_f64(offset.y as f64 / panned.zoom),
+            ];
+            let exact = exact_count(FractalType::Mandelbrot, c, panned.max_iter);
+            hits += (result.x as u32 == exact) as u32;
+            counts.push(exact);
+        }
+    }
+    counts.sort_unstable();
+    counts.dedup();
+    assert!(counts.len() >= 5, "featureless ({counts:?})");
+    assert!(hits as f64 >= 0.95 * (WIDTH * HEIGHT) as f64, "{hits} pixels match");
+}
+
+#[test]
+fn deep_iteration_limit_fits_the_orbit_texture() {
+    let webgl2 = wgpu::Limits::downlevel_webgl2_defaults();
+    assert_eq!(REFERENCE_WIDTH, webgl2.max_texture_dimension_2d);
+    assert!(REFERENCE_ROWS <= webgl2.max_texture_dimension_2d);
+
+    // Just past the cusp of the main cardioid, c = 1/4 + e escapes after about pi / sqrt(e) steps;
+    // the view has to be far narrower than e^(3/2), or its top and bottom are inside the set
+    let params = FractalParams {
+        center: [0.25 + 1e-9, 0.0].map(Coordinate::from_f64),
+        zoom: 1e16,
+        max_iter: 200_000,
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        ..FractalParams::default()
+    };
+    assert!(params.perturbation());
+    assert_eq!(params.max_iterations(), 1_000_000);
+    assert!(REFERENCE_WIDTH * REFERENCE_ROWS > params.max_iterations());
+    let orbit = ReferenceOrbit::new(&params);
+    let uniform = orbit.uniform(&params);
+    assert!(orbit.points.len() > 50_000, "{}", orbit.points.len());
+
+    let roots = NewtonRoots::new(&params);
+    let mut hits = 0;
+    let mut counts = Vec::new();
+    for y in (0..HEIGHT).step_by(3) {
+        for x in (0..WIDTH).step_by(3) {
+            let (result, _) = cpu::iterate_pixel(&params, &roots, Some((&orbit, &uniform)), x, y, WIDTH, HEIGHT);
+            let offset = cpu::pixel_offset(&params, x as f32, y as f32, WIDTH, HEIGHT);
+            let c = [
+                params.center[0] + Coordinate::from_f64(offset.x as f64 / params.zoom),
+                params.center[1] + Coordinate::from_f64(offset.y as f64 / params.zoom),
+            ];
+            let exact = exact_count(FractalType::Mandelbrot, c.map(|c| c.resize::<4>()), params.max_iter);
+            hits += (result.x as u32 == exact) as u32;
+            counts.push(exact);
+        }
+    }
+    assert!(counts.iter().all(|&n| n > 10_000 && n < params.max_iter), "{counts:?}");
+    assert!(hits as f64 >= 0.95 * counts.len() as f64, "{hits} of {} pixels match", counts.len());
+}
+
+#[test]
+fn rebases_when_the_reference_escapes_first() {
+    // Just off c = i, so the center escapes while pixels nearer the dendrite outlast it
+    let zoom = 1e30;
+    let center = [Fixed::<8>::from_f64(0.3 / zoom), Fixed::from_f64(1.0)];
+    let orbit = reference_orbit(FractalType::Mandelbrot, center, 1000, ESCAPE_RADIUS);
+    let counts = assert_agrees(FractalType::Mandelbrot, center, zoom, 1000);
+    assert!(counts.last().unwrap() + 1 > orbit.len() as u32, "{} vs {counts:?}", orbit.len());
+}
+
+#[test]
+fn series_matches_iterated_deltas() {
+    let center = [-0.101_096_363_845_62, 0.956_286_510_809_14].map(Fixed::<4>::from_f64);
+    let orbit = reference_orbit(FractalType::Mandelbrot, center, 2000, ESCAPE_RADIUS);
+    let uniform = PerturbationParams::new(1e12, orbit.len());
+    let series = view_series(&orbit, &uniform, 2000);
+    assert!(series.skip >= 20, "skipped only {}", series.skip);
+
+    // Deltas iterated one step at a time, scaled like the series
+    let scale = 2.0_f64.powi(-uniform.scale_exp);
+    let aspect = WIDTH as f64 / HEIGHT as f64;
+    let pixel = 2.0 * uniform.pixel_scale as f64 / HEIGHT as f64;
+    let linear = series.coefficients[0][0].hypot(series.coefficients[0][1]);
+    for (x, y) in [(0.3, -0.7), (-0.9, 0.9), (0.05, 0.0), (1.0, 1.0)] {
+        let w0 = [x * aspect * uniform.pixel_scale as f64, y * uniform.pixel_scale as f64];
+        let mut w = [0.0, 0.0];
+        for z in &orbit[..series.skip as usize] {
+            let z = z.map(f64::from);
+            w = [
+                2.0 * (z[0] * w[0] - z[1] * w[1]) + (w[0] * w[0] - w[1] * w[1]) * scale + w0[0],
+                2.0 * (z[0] * w[1] + z[1] * w[0]) + 2.0 * w[0] * w[1] * scale + w0[1],
+            ];
+        }
+        let approx = series.eval(w0);
+        let error = (approx[0] - w[0]).hypot(approx[1] - w[1]);
+        assert!(error <= 2e-3 * pixel * linear, "({x}, {y}) off by {} pixels", error / (pixel * linear));
+    }
+}
+
+#[test]
+fn series_skips_iterations_without_changing_counts() {
+    let views = [
+        ([-0.101_096_363_845_62, 0.956_286_510_809_14], 1e12, 500),
+        ([0.0, 1.0], 1e30, 1000),
+        ([-0.743_643_887_037_158, 0.131_825_904_205_33], 1e14, 3000),
+    ];
+    for (center, zoom, max_iter) in views {
+        let center = center.map(Fixed::<8>::from_f64);
+        let orbit = reference_orbit(FractalType::Mandelbrot, center, max_iter, ESCAPE_RADIUS);
+        let skip = view_series(&orbit, &PerturbationParams::new(zoom, orbit.len()), max_iter).skip;
+        assert!(skip > 0, "nothing skipped at {zoom:e}");
+
+        let (share, counts) = agreement(FractalType::Mandelbrot, center, zoom, max_iter, true);
+        assert!(counts.len() >= 5, "featureless at {zoom:e} ({counts:?})");
+        assert!(share >= 0.95, "agreed on {:.0}% of pixels at {zoom:e}", share * 100.0);
+        assert!(counts[0] >= skip, "skipped {skip} past an escape at {}", counts[0]);
+    }
+}
+
+#[test]
+fn series_stops_before_probes_leave_the_reference() {
+    // The reference escapes first here, so the series must stop short of its end
+    let zoom = 1e30;
+    let center = [Fixed::<8>::from_f64(0.3 / zoom), Fixed::from_f64(1.0)];
+    let orbit = reference_orbit(FractalType::Mandelbrot, center, 1000, ESCAPE_RADIUS);
+    let series = view_series(&orbit, &PerturbationParams::new(zoom, orbit.len()), 1000);
+    assert!((series.skip as usize) + 2 <= orbit.len(), "{} of {}", series.skip, orbit.len());
+    let (share, _) = agreement(FractalType::Mandelbrot, center, zoom, 1000, true);
+    assert!(share >= 0.95, "agreed on {:.0}% of pixels", share * 100.0);
+
+    // Nothing to skip with no probes to vouch for it
+    assert_eq!(series_approximation(&orbit, 99, &[], 1.0, 0, ESCAPE_RADIUS).skip, 0);
+}
+
+#[test]
+fn series_is_only_used_for_mandelbrot() {
+    let mut params = FractalParams {
+        resolution: [WIDTH as f32, HEIGHT as f32],
+        zoom: 1e10,
+        max_iter: 1000,
+        ..Default::default()
+    };
+    params.set_center([-0.101_096_363_845_62, 0.956_286_510_809_14]);
+    assert!(ReferenceOrbit::new(&params).uniform(&params).skip > 0);
+
+    params.set_fractal_type(FractalType::BurningShip);
+    params.set_center([-1.762_413_532_367_217, -0.028_011_935_432_613]);
+    assert_eq!(ReferenceOrbit::new(&params).uniform(&params).skip, 0);
+}
+
@@ -0,0 +1,58 @@
+use fractal_madness::presets::{parse_pack, PresetLibrary, PRESETS_VERSION};
+use fractal_madness::FractalType;
+
+fn pack(presets: &str) -> String {
+    format!(r#"{{"version":{PRESETS_VERSION},"presets":[{presets}]}}"#)
+}
+
+#[test]
+fn builtin_presets_cover_every_fractal_type() {
+    let library = PresetLibrary::builtin();
+    for ft in FractalType::all() {
+        let presets: Vec<_> = library.for_type(*ft).collect();
+        assert!(!presets.is_empty(), "no presets for {}", ft.name());
+        assert!(presets.iter().all(|preset| preset.fractal_type == *ft));
+    }
+    assert_eq!(library.for_type(FractalType::Mandelbrot).next().unwrap().name, "Overview");
+}
+
+#[test]
+fn packs_are_added_after_existing_presets() {
+    let mut library = PresetLibrary::builtin();
+    let before = library.len();
+    let added = parse_pack(&pack(
+        r#"{"name":"Team Spot","fractal_type":"BurningShip","center":[-1.76,-0.03],"zoom":40.0}"#,
+    ))
+    .unwrap();
+    library.add_pack(added);
+
+    assert_eq!(library.len(), before + 1);
+    let last = library.for_type(FractalType::BurningShip).last().unwrap();
+    assert_eq!(last.name, "Team Spot");
+    assert_eq!(last.power, None);
+}
+
+#[test]
+fn unknown_fractal_types_are_rejected() {
+    let err = parse_pack(&pack(r#"{"name":"x","fractal_type":"Sierpinski","center":[0,0],"zoom":1.0}"#));
+    assert!(err.is_err());
+}
+
+#[test]
+fn out_of_range_values_reject_the_pack() {
+    for preset in [
+        r#"{"name":"x","fractal_type":"Julia","center":[0,0],"zoom":0.0}"#,
+        r#"{"name":"x","fractal_type":"Julia","center":[0,0],"zoom":1.0,"power":20.0}"#,
+        r#"{"name":"x","fractal_type":"Julia","center":[0,0],"zoom":1.0,"julia_c":[3.0,0.0]}"#,
+        r#"{"name":" ","fractal_type":"Julia","center":[0,0],"zoom":1.0}"#,
+    ] {
+        let err = parse_pack(&pack(preset)).unwrap_err();
+        assert!(err.starts_with("Preset"), "{err}");
+    }
+}
+
+#[test]
+fn future_versions_are_rejected() {
+    let future = format!(r#"{{"version":{},"presets":[]}}"#, PRESETS_VERSION + 1);
+    assert!(parse_pack(&future).is_err());
+}
@@ -0,0 +1,108 @@
+use fractal_madness::scene::{Scene, SCENE_VERSION};
+use fractal_madness::{FractalParams, FractalType};
+
+/// A valid view with every scene field away from its default
+fn view() -> FractalParams {
+    let mut params = FractalParams {
+        zoom: 1234.5,
+        max_iter: 640,
+        power: 3.0,
+        escape_radius: 6.0,
+        color_scheme: 4,
+        julia_c: [0.285, 0.01],
+        rotation: -1.25,
+        ..FractalParams::default()
+    };
+    params.set_fractal_type(FractalType::Julia);
+    params.set_center([0.25, -0.125]);
+    params.set_smooth(false);
+    params.set_offset(true);
+    params
+}
+
+/// `params` with the per-frame fields a scene does not keep
+fn restore(loaded: FractalParams) -> FractalParams {
+    FractalParams { resolution: FractalParams::default().resolution, ..loaded }
+}
+
+#[test]
+fn scenes_round_trip_in_both_formats() {
+    let params = view();
+    let scene = Scene::new(&params);
+
+    let json = scene.to_json().unwrap();
+    assert_eq!(Scene::from_json(&json), Ok(scene.clone()));
+    assert_eq!(Scene::from_file("view.json", &json).unwrap().validate().map(restore), Ok(params));
+
+    let ron = scene.to_ron().unwrap();
+    assert_eq!(Scene::from_ron(&ron), Ok(scene.clone()));
+    assert_eq!(Scene::from_file("view.ron", &ron).unwrap().validate().map(restore), Ok(params));
+
+    // Readable names rather than raw enum values
+    assert!(json.contains("\"Julia\""), "{json}");
+    assert!(Scene::from_json(&ron).is_err());
+}
+
+#[test]
+fn out_of_range_values_are_clamped() {
+    let mut params = view();
+    params.zoom = 1e300;
+    params.max_iter = 0;
+    params.power = 20.0;
+    params.escape_radius = 1e6;
+    params.julia_c = [-9.0, 9.0];
+    params.rotation = 7.0;
+    params.flags |= 1 << 20;
+
+    let valid = Scene::new(&params).validate().unwrap();
+    assert_eq!(valid.zoom, 1e100);
+    assert_eq!(valid.max_iter, 10);
+    assert_eq!(valid.power, 8.0);
+    assert_eq!(valid.escape_radius, 100.0);
+    assert_eq!(valid.julia_c, [-2.0, 2.0]);
+    assert!((valid.rotation - (7.0 - std::f32::consts::TAU)).abs() < 1e-6, "{}", valid.rotation);
+    assert_eq!(valid.flags, params.flags & !(1 << 20));
+}
+
+#[test]
+fn deep_views_keep_more_iterations() {
+    let mut deep = FractalParams { zoom: 1e30, max_iter: 200_000, ..FractalParams::default() };
+    assert_eq!(Scene::new(&deep).validate().unwrap().max_iter, 200_000);
+    deep.max_iter = 5_000_000;
+    assert_eq!(Scene::new(&deep).validate().unwrap().max_iter, 1_000_000);
+
+    let shallow = FractalParams { zoom: 10.0, max_iter: 200_000, ..FractalParams::default() };
+    assert_eq!(Scene::new(&shallow).validate().unwrap().max_iter, 10_000);
+
+    // The canvas the view was saved on is not part of the scene and does not matter
+    for height in [1.0, 100_000.0] {
+        let mut params = FractalParams { zoom: 1e9, max_iter: 200_000, ..FractalParams::default() };
+        params.resolution[1] = height;
+        assert_eq!(Scene::new(&params).validate().unwrap().max_iter, 200_000);
+        let params = FractalParams { zoom: 10.0, ..params };
+        assert_eq!(Scene::new(&params).validate().unwrap().max_iter, 10_000);
+    }
+}
+
+#[test]
+fn invalid_scenes_are_rejected() {
+    let broken = [
+        FractalParams { zoom: f64::NAN, ..view() },
+        FractalParams { zoom: f64::INFINITY, ..view() },
+        FractalParams { power: f32::INFINITY, ..view() },
+        FractalParams { julia_c: [0.0, f32::NAN], ..view() },
+        FractalParams { zoom: 0.0, ..view() },
+        FractalParams { zoom: -2.0, ..view() },
+    ];
+    for params in broken {
+        assert!(Scene::new(&params).validate().is_err(), "{params:?}");
+    }
+
+    for version in [0, SCENE_VERSION + 1] {
+        let scene = Scene { version, params: view() };
+        assert!(scene.validate().is_err(), "version {version}");
+    }
+
+    assert!(Scene::from_json("{\"version\": 1, \"params\": {\"fractal_type\": \"Nope\"}}").is_err());
+    assert!(Scene::from_json("not json").is_err());
+}
@@ -0,0 +1,200 @@
+use fractal_madness::viewport::wrap_angle;
+use fractal_madness::{cpu, Coordinate, FractalParams, Viewport};
+use proptest::prelude::*;
+
+/// Tolerance in pixels for round trips through the complex plane
+const EPSILON: f64 = 1e-6;
+/// Rounding error allowed in a complex coordinate, in units of f64::EPSILON
+/// (coordinates stay below about 4 in magnitude and pass through a few operations)
+const COORDINATE_ULPS: f64 = 32.0;
+
+fn viewport() -> impl Strategy<Value = Viewport> {
+    (
+        (-2.0..2.0f64, -2.0..2.0f64),
+        -3.0..6.0f64,
+        -std::f64::consts::PI..std::f64::consts::PI,
+        (64.0..4096.0f64, 64.0..4096.0f64),
+        0.0..0.5f64,
+    )
+        .prop_map(|((cx, cy), log_zoom, rotation, (width, height), panel)| {
+            let mut viewport = Viewport {
+                center: [cx, cy].map(Coordinate::from_f64),
+                zoom: 10f64.powf(log_zoom),
+                rotation,
+                resolution: [width, height],
+                offset: [0.0, 0.0],
+            };
+            viewport.set_panel_width(panel * width);
+            viewport
+        })
+}
+
+fn screen_point() -> impl Strategy<Value = [f64; 2]> {
+    (0.0..1.0f64, 0.0..1.0f64).prop_map(|(x, y)| [x, y])
+}
+
+fn scale(viewport: &Viewport, p: [f64; 2]) -> [f64; 2] {
+    [p[0] * viewport.resolution[0], p[1] * viewport.resolution[1]]
+}
+
+/// Pixel tolerance for `viewport`: deep zooms cannot resolve finer than the
+/// rounding of the complex coordinates, which then spans many pixels
+fn tolerance(viewport: &Viewport) -> f64 {
+    EPSILON.max(COORDINATE_ULPS * f64::EPSILON / viewport.pixel_size())
+}
+
+fn assert_close(a: [f64; 2], b: [f64; 2], tolerance: f64) -> Result<(), TestCaseError> {
+    prop_assert!(
+        (a[0] - b[0]).abs() < tolerance && (a[1] - b[1]).abs() < tolerance,
+        "{:?} != {:?}",
+        a,
+        b
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn screen_complex_round_trip(viewport in viewport(), p in screen_point()) {
+        let p = scale(&viewport, p);
+        assert_close(viewport.complex_to_screen(viewport.screen_to_complex(p)), p, tolerance(&viewport))?;
+    }
+
+    #[test]
+    fn drag_moves_content_by_exact_pixels(
+        viewport in viewport(),
+        p in screen_point(),
+        dx in -500.0..500.0f64,
+        dy in -500.0..500.0f64,
+    ) {
+        let p = scale(&viewport, p);
+        let c = viewport.screen_to_complex(p);
+
+        let mut dragged = viewport;
+        dragged.pan(dx, dy);
+
+        assert_close(dragged.complex_to_screen(c), [p[0] + dx, p[1] + dy], tolerance(&dragged))?;
+    }
+
+    #[test]
+    fn zoom_at_keeps_point_fixed(viewport in viewport(), p in screen_point(), factor in 0.1..10.0f64) {
+        let p = scale(&viewport, p);
+        let c = viewport.screen_to_complex(p);
+
+        let mut zoomed = viewport;
+        zoomed.zoom_at(factor, p);
+
+        assert_close(zoomed.complex_to_screen(c), p, tolerance(&zoomed))?;
+    }
+
+    #[test]
+    fn rotate_at_keeps_point_fixed(viewport in viewport(), p in screen_point(), angle in -7.0..7.0f64) {
+        let p = scale(&viewport, p);
+        let c = viewport.screen_to_complex(p);
+
+        let mut rotated = viewport;
+        rotated.rotate_at(angle, p);
+
+        assert_close(rotated.complex_to_screen(c), p, tolerance(&rotated))?;
+        prop_assert!(rotated.rotation > -std::f64::consts::PI && rotated.rotation <= std::f64::consts::PI);
+    }
+}
+
+#[test]
+fn matches_shader_mapping() {
+    let params = FractalParams {
+        center: [-0.5, 0.25].map(Coordinate::from_f64),
+        zoom: 3.0,
+        resolution: [1280.0, 800.0],
+        ui_offset: -0.35,
+        ui_offset_y: 0.1,
+        ..FractalParams::default()
+    };
+    let viewport = Viewport::from_params(&params);
+
+    // fs_main: c = center + ((uv - 0.5) * 2 * (aspect, 1) + offset) / zoom
+    let uv = [0.25, 0.75];
+    let aspect = 1280.0 / 800.0;
+    let expected = [
+        -0.5 + ((uv[0] - 0.5) * 2.0 * aspect - 0.35) / 3.0,
+        0.25 + ((uv[1] - 0.5) * 2.0 + 0.1) / 3.0,
+    ];
+    let c = viewport.screen_to_complex([uv[0] * 1280.0, uv[1] * 800.0]);
+    assert!((c[0] - expected[0]).abs() < 1e-6 && (c[1] - expected[1]).abs() < 1e-6);
+}
+
+#[test]
+fn rotation_matches_cpu_mapping() {
+    let params = FractalParams {
+        center: [-0.5, 0.25].map(Coordinate::from_f64),
+        zoom: 3.0,
+        rotation: 0.7,
+        resolution: [1280.0, 800.0],
+        ui_offset: -0.35,
+        ..FractalParams::default()
+    };
+    let viewport = Viewport::from_params(&params);
+
+    for (x, y) in [(0, 0), (100, 700), (1279, 799), (640, 400)] {
+        let c = viewport.screen_to_complex([x as f64 + 0.5, y as f64 + 0.5]);
+        let gpu = cpu::pixel_to_complex(&params, x as f32, y as f32, 1280, 800);
+        assert!((c[0] - gpu.x as f64).abs() < 1e-5 && (c[1] - gpu.y as f64).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn angles_wrap_into_half_open_range() {
+    use std::f64::consts::PI;
+    assert_eq!(wrap_angle(PI), PI);
+    assert_eq!(wrap_angle(-PI), PI);
+    assert!((wrap_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-12);
+    assert!((wrap_angle(-5.0 * PI / 2.0) + PI / 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn panel_offset_centers_visible_area() {
+    let mut viewport = Viewport {
+        center: [0.3, -0.2].map(Coordinate::from_f64),
+        zoom: 2.0,
+        rotation: 0.0,
+        resolution: [1000.0, 500.0],
+        offset: [0.0, 0.0],
+    };
+    viewport.set_panel_width(200.0);
+
+    // The visible area spans x = 200..1000, so its middle is x = 600
+    let c = viewport.screen_to_complex([600.0, 250.0]);
+    assert!((c[0] - 0.3).abs() < 1e-12 && (c[1] + 0.2).abs() < 1e-12);
+}
+
+proptest! {
+    #[test]
+    fn box_zoom_out_undoes_box_zoom_in(
+        viewport in viewport(),
+        a in screen_point(),
+        b in screen_point(),
+    ) {
+        let (a, b) = (scale(&viewport, a), scale(&viewport, b));
+        let min = [a[0].min(b[0]), a[1].min(b[1])];
+        let max = [a[0].max(b[0]), a[1].max(b[1])];
+        prop_assume!(max[0] - min[0] > 8.0 && max[1] - min[1] > 8.0);
+
+        let mut zoomed = viewport;
+        zoomed.zoom_to_rect(min, max, false);
+        prop_assume!(zoomed.zoom < 1e9);
+
+        // The box center is now the visible center and the box fits in the visible area
+        let corner_a = zoomed.complex_to_screen(viewport.screen_to_complex(min));
+        let corner_b = zoomed.complex_to_screen(viewport.screen_to_complex(max));
+        let visible = zoomed.visible_size();
+        prop_assert!((corner_b[0] - corner_a[0]) <= visible[0] + tolerance(&zoomed));
+        prop_assert!((corner_b[1] - corner_a[1]) <= visible[1] + tolerance(&zoomed));
+
+        zoomed.zoom_to_rect(min, max, true);
+        prop_assert!((zoomed.zoom / viewport.zoom - 1.0).abs() < 1e-9);
+        let pixel = viewport.pixel_size();
+        let moved = [0, 1].map(|i| (zoomed.center[i] - viewport.center[i]).to_f64());
+        prop_assert!(moved[0].abs() < tolerance(&viewport) * pixel);
+        prop_assert!(moved[1].abs() < tolerance(&viewport) * pixel);
+    }
+}
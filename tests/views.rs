@@ -0,0 +1,84 @@
+use fractal_madness::views::{initial_view, FractalViews, VIEWS_VERSION};
+use fractal_madness::{FractalParams, FractalType};
+
+fn julia_spot() -> FractalParams {
+    let mut params = initial_view(FractalType::Julia);
+    params.set_center([0.31, -0.12]);
+    params.zoom = 42.0;
+    params.max_iter = 900;
+    params.power = 3.0;
+    params.escape_radius = 16.0;
+    params.julia_c = [-0.8, 0.156];
+    params.color_scheme = 7;
+    params.flags = FractalParams::FLAG_INVERT;
+    params
+}
+
+#[test]
+fn switching_back_restores_every_parameter() {
+    let mut views = FractalViews::new();
+    let mut params = FractalParams {
+        resolution: [800.0, 600.0],
+        ui_offset: 0.3,
+        ..julia_spot()
+    };
+
+    views.switch(&mut params, FractalType::Newton);
+    assert_eq!(params.get_fractal_type(), FractalType::Newton);
+    assert_eq!(params.max_iter, initial_view(FractalType::Newton).max_iter);
+    assert_eq!(params.resolution, [800.0, 600.0]);
+
+    views.switch(&mut params, FractalType::Julia);
+    assert_eq!(
+        params,
+        FractalParams {
+            resolution: [800.0, 600.0],
+            ui_offset: 0.3,
+            ..julia_spot()
+        }
+    );
+}
+
+#[test]
+fn unvisited_types_start_from_their_defaults() {
+    let views = FractalViews::new();
+    let phoenix = views.recall(FractalType::Phoenix);
+    assert_eq!(phoenix.get_fractal_type(), FractalType::Phoenix);
+    assert_eq!(phoenix.color_scheme, FractalType::Phoenix.default_color_scheme());
+    assert_eq!(phoenix.julia_c, [0.5667, -0.5]);
+    assert_eq!(views.current().get_fractal_type(), FractalType::Mandelbrot);
+}
+
+#[test]
+fn json_round_trip_keeps_views_and_current_type() {
+    let mut views = FractalViews::new();
+    let mut params = julia_spot();
+    views.switch(&mut params, FractalType::BurningShip);
+    views.switch(&mut params, FractalType::Julia);
+
+    let loaded = FractalViews::from_json(&views.to_json().unwrap()).unwrap();
+    assert_eq!(loaded, views);
+    assert_eq!(loaded.current(), julia_spot());
+}
+
+#[test]
+fn invalid_views_are_skipped() {
+    let json = format!(
+        r#"{{"version":{VIEWS_VERSION},"current":"Julia","views":[
+            {{"fractal_type":"Julia","center":[0.5,0.5],"zoom":3.0}},
+            {{"fractal_type":"BurningShip","zoom":-1.0}}
+        ]}}"#
+    );
+    let views = FractalViews::from_json(&json).unwrap();
+    assert_eq!(views.current().center_f64(), [0.5, 0.5]);
+    assert_eq!(views.recall(FractalType::BurningShip), initial_view(FractalType::BurningShip));
+}
+
+#[test]
+fn unknown_fractal_types_and_future_versions_are_rejected() {
+    let json = format!(r#"{{"version":{VIEWS_VERSION},"current":"Sierpinski","views":[]}}"#);
+    assert!(FractalViews::from_json(&json).is_err());
+
+    let json = format!(r#"{{"version":{},"current":"Julia","views":[]}}"#, VIEWS_VERSION + 1);
+    assert!(FractalViews::from_json(&json).is_err());
+}
@@ -7,6 +7,9 @@ export default defineConfig({
   root: path.resolve(__dirname, "web"),
   base: "/",
   plugins: [wasm(), topLevelAwait()],
+  worker: {
+    format: "es",
+  },
   build: {
     outDir: "../dist",
     target: "es2022",
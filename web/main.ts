@@ -1,4 +1,4 @@
-import init, { run } from "../pkg/fractal_madness.js";
+import init, { run, set_orbit_worker } from "../pkg/fractal_madness.js";
 
 async function main() {
   const errorBanner = document.getElementById("error-banner");
@@ -13,6 +13,8 @@ async function main() {
   try {
     // Initialize the WASM module
     await init();
+    // Reference orbits for deep zooms are computed in a worker so the page stays responsive
+    set_orbit_worker(new Worker(new URL("./orbit-worker.ts", import.meta.url), { type: "module" }));
     // Run the application
     await run();
   } catch (error) {
@@ -0,0 +1,11 @@
+// Computes reference orbits for deep zooms off the main thread (OrbitJob in src/perturbation.rs)
+import init, { compute_reference_orbit } from "../pkg/fractal_madness.js";
+
+const ready = init();
+
+self.onmessage = async (event: MessageEvent<[number, string]>) => {
+  await ready;
+  const [id, request] = event.data;
+  const points = compute_reference_orbit(request);
+  self.postMessage([id, points], { transfer: [points.buffer] });
+};